@@ -1,6 +1,6 @@
 use std::{
     fs::File,
-    io::{BufReader, BufWriter, Read, Write},
+    io::{BufReader, BufWriter, Cursor, Read, Write},
     path::PathBuf,
 };
 
@@ -17,18 +17,24 @@ use winit::raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 use iced::{
     Alignment, Element, Length, Task,
     widget::{
-        self, Column, button, column, horizontal_space, row,
-        text_editor::Content, text_input, vertical_space,
+        self, Column, button, checkbox, column, horizontal_space, pick_list,
+        row, text_editor::Content, text_input, vertical_space,
     },
 };
-use itertools::Itertools;
-use vigenere_rs::Vigenere;
+use vigenere_rs::{
+    ByteVigenere, Cipher, CipherError, KeyError, KeyKind, KeyPolicy,
+};
 
 use crate::MainMessage;
 
 const FILE_DIALOG_NAME: &str = "CHOOSE FILE";
 const ERR_BAD_PASSWORD: &str =
     "Password should be only-ASCII-alphabetic and non-empty";
+const ERR_BAD_KEY: &str = "Key doesn't suit the chosen cipher";
+const ERR_BAD_NUMERIC_KEY: &str =
+    "Gronsfeld key should be only digits and non-empty";
+const ERR_BAD_KEY_FILE: &str =
+    "Key file should be UTF-8 text and the offset a non-negative number";
 
 const IDENTIFYING_MESSAGE: &str = "M%S$&#%";
 
@@ -48,11 +54,29 @@ impl FileOrText {
     }
 }
 
+/// Entry of the cipher picker, pointing into the registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CipherChoice {
+    index: usize,
+    name: &'static str,
+}
+
+impl std::fmt::Display for CipherChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
 #[derive(Debug)]
 pub struct KeyChooseView {
     key: String,
     input: FileOrText,
     output_path: Option<PathBuf>,
+    binary: bool,
+    ciphers: Vec<Box<dyn Cipher>>,
+    selected: usize,
+    key_file: Option<PathBuf>,
+    key_offset: String,
 }
 
 #[derive(Debug, Clone)]
@@ -65,16 +89,24 @@ pub enum KeyChooseMessage {
     Cipher,
     Decipher,
     BigtextAction(iced::widget::text_editor::Action),
+    BinaryToggled(bool),
+    CipherSelected(CipherChoice),
+    KeyFileChoose,
+    KeyFileSelected(PathBuf),
+    KeyOffsetInput(String),
 }
 
 impl KeyChooseView {
-    const N_VALUES: usize = 4;
-
     pub fn new() -> Self {
         Self {
             key: String::new(),
             input: FileOrText::Text(Content::new()),
             output_path: None,
+            binary: false,
+            ciphers: vigenere_rs::registry(),
+            selected: 0,
+            key_file: None,
+            key_offset: String::new(),
         }
     }
 
@@ -90,78 +122,24 @@ impl KeyChooseView {
         match msg {
             KeyChooseMessage::TextboxInput(thing) => self.key = thing,
             KeyChooseMessage::InFileChoose => {
-                let file = rfd::FileDialog::new()
-                    .set_title(FILE_DIALOG_NAME)
-                    .pick_file();
-
-                if let Some(file) = file {
-                    return Self::task(KeyChooseMessage::InFileSelected(file));
-                }
-
-                println!("InFile not selected");
+                return Self::pick_file(
+                    KeyChooseMessage::InFileSelected,
+                    "InFile not selected",
+                );
             }
             KeyChooseMessage::InFileSelected(file) => {
                 self.input = FileOrText::File(file);
             }
-            KeyChooseMessage::OutFileChoose => {
-                #[cfg(target_os = "windows")]
-                let file = {
-                    let hwnd = unsafe { GetForegroundWindow() };
-                    let handle =
-                        iced::window::raw_window_handle::Win32WindowHandle::new(
-                            std::num::NonZero::<isize>::new(hwnd.0 as isize).expect("Couldn't get current HWND"),
-                        );
-                    let handle = unsafe {
-                        iced::window::raw_window_handle::WindowHandle::borrow_raw(RawWindowHandle::Win32(handle))
-                    };
-                    let display = DisplayHandle::windows();
-
-                    struct DisplayAndWinHandle<'a>(
-                        DisplayHandle<'a>,
-                        WindowHandle<'a>,
-                    );
-                    impl<'a> HasDisplayHandle for DisplayAndWinHandle<'a> {
-                        fn display_handle(
-                            &self,
-                        ) -> Result<
-                            DisplayHandle<'_>,
-                            winit::raw_window_handle::HandleError,
-                        > {
-                            Ok(self.0.clone())
-                        }
-                    }
-
-                    impl<'a> HasWindowHandle for DisplayAndWinHandle<'a> {
-                        fn window_handle(
-                            &self,
-                        ) -> Result<
-                            WindowHandle<'_>,
-                            winit::raw_window_handle::HandleError,
-                        > {
-                            Ok(self.1.clone())
-                        }
-                    }
-
-                    rfd::FileDialog::new()
-                        .set_title(FILE_DIALOG_NAME)
-                        .set_parent(&DisplayAndWinHandle(display, handle))
-                        .save_file()
-                };
-
-                #[cfg(not(target_os = "windows"))]
-                let file = rfd::FileDialog::new()
-                    .set_title(FILE_DIALOG_NAME)
-                    .save_file();
-
-                if let Some(file) = file {
-                    return Self::task(KeyChooseMessage::OutFileSelected(file));
-                }
-
-                println!("OutFile not chosen");
-            }
+            KeyChooseMessage::OutFileChoose => return Self::save_file(),
             KeyChooseMessage::OutFileSelected(path) => {
                 self.output_path = Some(path);
             }
+            KeyChooseMessage::Cipher if self.binary => {
+                return self.cipher_bytes();
+            }
+            KeyChooseMessage::Decipher if self.binary => {
+                return self.decipher_bytes();
+            }
             KeyChooseMessage::Cipher => {
                 return self.cipher();
             }
@@ -173,184 +151,335 @@ impl KeyChooseView {
                     text.perform(action);
                 }
             }
+            KeyChooseMessage::BinaryToggled(binary) => self.binary = binary,
+            KeyChooseMessage::CipherSelected(choice) => {
+                self.selected = choice.index;
+            }
+            KeyChooseMessage::KeyFileChoose => {
+                return Self::pick_file(
+                    KeyChooseMessage::KeyFileSelected,
+                    "Key file not selected",
+                );
+            }
+            KeyChooseMessage::KeyFileSelected(file) => {
+                self.key_file = Some(file);
+            }
+            KeyChooseMessage::KeyOffsetInput(offset) => {
+                self.key_offset = offset;
+            }
         }
 
         Task::none()
     }
 
-    fn cipher(&mut self) -> Task<MainMessage> {
-        let Some(vigenere) = Vigenere::new(self.key.clone()) else {
-            return Task::done(MainMessage::Error(ERR_BAD_PASSWORD));
-        };
+    /// Asks for a file to read and passes it on as `selected`.
+    fn pick_file(
+        selected: fn(PathBuf) -> KeyChooseMessage,
+        missing: &str,
+    ) -> Task<MainMessage> {
+        let file = rfd::FileDialog::new()
+            .set_title(FILE_DIALOG_NAME)
+            .pick_file();
+
+        if let Some(file) = file {
+            return Self::task(selected(file));
+        }
 
-        match self.input.take() {
-            FileOrText::File(path_buf) => {
-                let Ok(file) = File::open(path_buf) else {
-                    return Self::err("Couldn't open input file");
-                };
-
-                let file = BufReader::new(file);
-
-                let bytes = file.bytes().filter_map(Result::ok).chunks(32);
-
-                let chars_iter = bytes
-                    .into_iter()
-                    .flat_map(|chunk| {
-                        String::from_utf8(chunk.collect::<Vec<u8>>())
-                    })
-                    .flat_map(|val| val.chars().collect::<Vec<_>>());
-
-                let result = vigenere.cipher(chars_iter);
-
-                let Some(outfile) = self.output_path.take() else {
-                    unreachable!("Cipher executed while outpath is None");
-                };
-
-                let Ok(outfile) = File::create(outfile) else {
-                    return Self::err("Couldn't create the file");
-                };
-
-                let mut outfile = BufWriter::new(outfile);
-
-                for slice in &result.chunks(Self::N_VALUES) {
-                    if outfile
-                        .write_all(slice.collect::<String>().as_bytes())
-                        .is_err()
-                    {
-                        return Self::err(
-                            "Something went wrong while writing result to the file",
-                        );
-                    }
-                }
+        println!("{missing}");
 
-                Task::none()
+        Task::none()
+    }
+
+    /// Asks where to write the output.
+    fn save_file() -> Task<MainMessage> {
+        #[cfg(target_os = "windows")]
+        let file = {
+            let hwnd = unsafe { GetForegroundWindow() };
+            let handle =
+                iced::window::raw_window_handle::Win32WindowHandle::new(
+                    std::num::NonZero::<isize>::new(hwnd.0 as isize)
+                        .expect("Couldn't get current HWND"),
+                );
+            let handle = unsafe {
+                iced::window::raw_window_handle::WindowHandle::borrow_raw(
+                    RawWindowHandle::Win32(handle),
+                )
+            };
+            let display = DisplayHandle::windows();
+
+            struct DisplayAndWinHandle<'a>(DisplayHandle<'a>, WindowHandle<'a>);
+            impl<'a> HasDisplayHandle for DisplayAndWinHandle<'a> {
+                fn display_handle(
+                    &self,
+                ) -> Result<
+                    DisplayHandle<'_>,
+                    winit::raw_window_handle::HandleError,
+                > {
+                    Ok(self.0.clone())
+                }
             }
-            FileOrText::Text(text) => {
-                let text = text.text();
-                let result = vigenere
-                    .cipher(IDENTIFYING_MESSAGE.chars().chain(text.chars()));
-                let Some(outfile) = self.output_path.take() else {
-                    return Self::err(
-                        "Cipher path executed while output path is none",
-                    );
-                };
-
-                let Ok(outfile) = File::create(outfile) else {
-                    return Self::err("Couldn't create the file");
-                };
-
-                let mut outfile = BufWriter::new(outfile);
-
-                for slice in &result.chunks(Self::N_VALUES) {
-                    if outfile
-                        .write_all(slice.collect::<String>().as_bytes())
-                        .is_err()
-                    {
-                        return Self::err(
-                            "Something went wrong while writing result to the file",
-                        );
-                    }
+
+            impl<'a> HasWindowHandle for DisplayAndWinHandle<'a> {
+                fn window_handle(
+                    &self,
+                ) -> Result<
+                    WindowHandle<'_>,
+                    winit::raw_window_handle::HandleError,
+                > {
+                    Ok(self.1.clone())
                 }
+            }
 
-                Task::none()
+            rfd::FileDialog::new()
+                .set_title(FILE_DIALOG_NAME)
+                .set_parent(&DisplayAndWinHandle(display, handle))
+                .save_file()
+        };
+
+        #[cfg(not(target_os = "windows"))]
+        let file = rfd::FileDialog::new()
+            .set_title(FILE_DIALOG_NAME)
+            .save_file();
+
+        if let Some(file) = file {
+            return Self::task(KeyChooseMessage::OutFileSelected(file));
+        }
+
+        println!("OutFile not chosen");
+
+        Task::none()
+    }
+
+    fn cipher_kind(&self) -> &dyn Cipher {
+        self.ciphers[self.selected].as_ref()
+    }
+
+    fn choices(&self) -> Vec<CipherChoice> {
+        self.ciphers
+            .iter()
+            .enumerate()
+            .map(|(index, cipher)| CipherChoice {
+                index,
+                name: cipher.name(),
+            })
+            .collect()
+    }
+
+    /// Key text starts at the offset typed next to the key file, or at the
+    /// beginning when nothing is typed.
+    fn book(&self) -> Option<String> {
+        let offset = match self.key_offset.trim() {
+            "" => 0,
+            offset => offset.parse().ok()?,
+        };
+
+        let book = std::fs::read_to_string(self.key_file.as_ref()?).ok()?;
+
+        Some(book.chars().skip(offset).collect())
+    }
+
+    /// The key as the chosen cipher takes it, checked up front so a bad key
+    /// is reported before the output file is touched.
+    fn cipher_key(&self) -> Result<String, &'static str> {
+        let cipher = self.cipher_kind();
+
+        let (key, err) = match cipher.key_kind() {
+            KeyKind::Book => {
+                (self.book().ok_or(ERR_BAD_KEY_FILE)?, ERR_BAD_KEY_FILE)
+            }
+            KeyKind::Digits => (self.key.clone(), ERR_BAD_NUMERIC_KEY),
+            KeyKind::None | KeyKind::Text => (self.key.clone(), ERR_BAD_KEY),
+        };
+
+        cipher.parse_key(&key).map_err(|key_err| match key_err {
+            KeyError::Policy(_) => ERR_BAD_PASSWORD,
+            _ => err,
+        })?;
+
+        Ok(key)
+    }
+
+    const fn cipher_err(err: &vigenere_rs::Error) -> &'static str {
+        match err {
+            vigenere_rs::Error::Key(_) => ERR_BAD_KEY,
+            vigenere_rs::Error::Cipher(CipherError::KeyExhausted {
+                ..
+            }) => "Key file is shorter than the message",
+            vigenere_rs::Error::Cipher(_) => {
+                "Message can't be ciphered with the chosen cipher"
             }
+            vigenere_rs::Error::Io(_) => "Couldn't read input as text",
         }
     }
 
-    fn decipher(&mut self) -> Task<MainMessage> {
-        let Some(vigenere) = Vigenere::new(self.key.clone()) else {
+    /// Whether there's enough key material to cipher with.
+    fn has_key(&self) -> bool {
+        if self.binary {
+            return !self.key.is_empty();
+        }
+
+        match self.cipher_kind().key_kind() {
+            KeyKind::None => true,
+            KeyKind::Book => self.key_file.is_some(),
+            KeyKind::Text | KeyKind::Digits => !self.key.is_empty(),
+        }
+    }
+
+    fn byte_vigenere(&self) -> Option<ByteVigenere> {
+        KeyPolicy::ascii_alphabetic().check(&self.key).ok()?;
+
+        ByteVigenere::new(self.key.as_bytes()).ok()
+    }
+
+    fn input_bytes(input: FileOrText) -> std::io::Result<Vec<u8>> {
+        match input {
+            FileOrText::File(path_buf) => std::fs::read(path_buf),
+            FileOrText::Text(text) => Ok(text.text().into_bytes()),
+        }
+    }
+
+    /// Binary mode: the input is taken byte by byte, so any file survives
+    /// the round trip, not just UTF-8 text.
+    fn cipher_bytes(&mut self) -> Task<MainMessage> {
+        let Some(vigenere) = self.byte_vigenere() else {
             return Task::done(MainMessage::Error(ERR_BAD_PASSWORD));
         };
 
-        match self.input.take() {
-            FileOrText::File(path_buf) => {
-                let Ok(file) = File::open(path_buf) else {
-                    return Self::err("Couldn't open input file");
-                };
+        let Ok(input) = Self::input_bytes(self.input.take()) else {
+            return Self::err("Couldn't open input file");
+        };
 
-                let file = BufReader::new(file);
+        let Some(outfile) = self.output_path.take() else {
+            return Self::err("Cipher path executed while output path is none");
+        };
 
-                let bytes = file.bytes().filter_map(Result::ok).chunks(32);
+        let result = vigenere
+            .cipher(IDENTIFYING_MESSAGE.bytes().chain(input))
+            .collect::<Vec<u8>>();
 
-                let chars_iter = bytes
-                    .into_iter()
-                    .flat_map(|chunk| {
-                        String::from_utf8(chunk.collect::<Vec<u8>>())
-                    })
-                    .flat_map(|val| val.chars().collect::<Vec<_>>());
+        if std::fs::write(outfile, result).is_err() {
+            return Self::err(
+                "Something went wrong while writing result to the file",
+            );
+        }
 
-                let mut result = vigenere.decipher(chars_iter);
+        Task::none()
+    }
 
-                let Some(outfile) = self.output_path.take() else {
-                    unreachable!("Cipher executed while outpath is None");
-                };
+    fn decipher_bytes(&mut self) -> Task<MainMessage> {
+        let Some(vigenere) = self.byte_vigenere() else {
+            return Task::done(MainMessage::Error(ERR_BAD_PASSWORD));
+        };
 
-                let Ok(outfile) = File::create(outfile) else {
-                    return Self::err("Couldn't create the file");
-                };
+        let Ok(input) = Self::input_bytes(self.input.take()) else {
+            return Self::err("Couldn't open input file");
+        };
 
-                let mut outfile = BufWriter::new(outfile);
+        let Some(outfile) = self.output_path.take() else {
+            unreachable!("Cipher executed while outpath is None");
+        };
 
-                let identmsg = result
-                    .by_ref()
-                    .take(IDENTIFYING_MESSAGE.len())
-                    .collect::<String>();
+        let result = vigenere.decipher_slice(&input);
 
-                if identmsg != IDENTIFYING_MESSAGE {
-                    return Self::err("Message was not ciphered in this app");
-                }
+        let Some(result) = result.strip_prefix(IDENTIFYING_MESSAGE.as_bytes())
+        else {
+            return Self::err("Message was not ciphered in this app");
+        };
 
-                for slice in &result.chunks(Self::N_VALUES) {
-                    if outfile
-                        .write_all(slice.collect::<String>().as_bytes())
-                        .is_err()
-                    {
-                        return Self::err(
-                            "Something went wrong while writing result to the file",
-                        );
-                    }
-                }
+        if std::fs::write(outfile, result).is_err() {
+            return Self::err(
+                "Something went wrong while writing result to the file",
+            );
+        }
+
+        Task::none()
+    }
 
-                Task::none()
+    fn input_reader(input: FileOrText) -> std::io::Result<Box<dyn Read>> {
+        match input {
+            FileOrText::File(path_buf) => {
+                Ok(Box::new(BufReader::new(File::open(path_buf)?)))
             }
             FileOrText::Text(text) => {
-                let text = text.text();
+                Ok(Box::new(Cursor::new(text.text().into_bytes())))
+            }
+        }
+    }
+
+    fn write_output(
+        mut result: impl Read,
+        outfile: PathBuf,
+    ) -> Task<MainMessage> {
+        let Ok(outfile) = File::create(outfile) else {
+            return Self::err("Couldn't create the file");
+        };
+
+        let mut outfile = BufWriter::new(outfile);
+
+        if std::io::copy(&mut result, &mut outfile)
+            .and_then(|_| outfile.flush())
+            .is_err()
+        {
+            return Self::err(
+                "Something went wrong while writing result to the file",
+            );
+        }
+
+        Task::none()
+    }
+
+    /// Text ciphers that can stream do so, the rest read the whole input
+    /// first.
+    fn cipher(&mut self) -> Task<MainMessage> {
+        let key = match self.cipher_key() {
+            Ok(key) => key,
+            Err(err) => return Self::err(err),
+        };
 
-                let mut result = vigenere.decipher(text.chars());
+        let Ok(input) = Self::input_reader(self.input.take()) else {
+            return Self::err("Couldn't open input file");
+        };
 
-                let Some(outfile) = self.output_path.take() else {
-                    unreachable!("Cipher executed while outpath is None");
-                };
+        let Some(outfile) = self.output_path.take() else {
+            return Self::err("Cipher path executed while output path is none");
+        };
 
-                let Ok(outfile) = File::create(outfile) else {
-                    return Self::err("Couldn't create the file");
-                };
+        let input = Box::new(IDENTIFYING_MESSAGE.as_bytes().chain(input));
 
-                let mut outfile = BufWriter::new(outfile);
+        match self.cipher_kind().encrypt_reader(&key, input) {
+            Ok(result) => Self::write_output(result, outfile),
+            Err(err) => Self::err(Self::cipher_err(&err)),
+        }
+    }
 
-                let identmsg = result
-                    .by_ref()
-                    .take(IDENTIFYING_MESSAGE.len())
-                    .collect::<String>();
+    fn decipher(&mut self) -> Task<MainMessage> {
+        let key = match self.cipher_key() {
+            Ok(key) => key,
+            Err(err) => return Self::err(err),
+        };
 
-                if identmsg != IDENTIFYING_MESSAGE {
-                    return Self::err("Message was not ciphered in this app");
-                }
+        let Ok(input) = Self::input_reader(self.input.take()) else {
+            return Self::err("Couldn't open input file");
+        };
 
-                for slice in &result.chunks(Self::N_VALUES) {
-                    if outfile
-                        .write_all(slice.collect::<String>().as_bytes())
-                        .is_err()
-                    {
-                        return Self::err(
-                            "Something went wrong while writing result to the file",
-                        );
-                    }
-                }
+        let Some(outfile) = self.output_path.take() else {
+            unreachable!("Cipher executed while outpath is None");
+        };
 
-                Task::none()
-            }
+        let mut result = match self.cipher_kind().decrypt_reader(&key, input) {
+            Ok(result) => result,
+            Err(err) => return Self::err(Self::cipher_err(&err)),
+        };
+
+        let mut identmsg = [0; IDENTIFYING_MESSAGE.len()];
+
+        if result.read_exact(&mut identmsg).is_err()
+            || identmsg != IDENTIFYING_MESSAGE.as_bytes()
+        {
+            return Self::err("Message was not ciphered in this app");
         }
+
+        Self::write_output(result, outfile)
     }
 
     pub fn view(&self) -> Element<'_, KeyChooseMessage> {
@@ -360,16 +489,71 @@ impl KeyChooseView {
             .into()
     }
 
+    /// Key file and offset for a running key, a text box otherwise.
+    fn key_input(&self) -> Element<'_, KeyChooseMessage> {
+        let key_kind = self.cipher_kind().key_kind();
+
+        if key_kind == KeyKind::Book && !self.binary {
+            let key_file = self
+                .key_file
+                .as_ref()
+                .and_then(|path| path.file_name())
+                .map_or_else(
+                    || "Choose key file".into(),
+                    |name| name.to_string_lossy().into_owned(),
+                );
+
+            row![
+                button(widget::text(key_file))
+                    .on_press(KeyChooseMessage::KeyFileChoose),
+                text_input("Start offset", &self.key_offset)
+                    .on_input(KeyChooseMessage::KeyOffsetInput),
+            ]
+            .spacing(10)
+            .width(Length::FillPortion(2))
+            .into()
+        } else {
+            let placeholder = match key_kind {
+                KeyKind::Digits if !self.binary => "Input your numeric key",
+                KeyKind::None if !self.binary => "This cipher needs no key",
+                _ => "Input your key",
+            };
+
+            text_input(placeholder, &self.key)
+                .secure(true)
+                .width(Length::FillPortion(2))
+                .on_input(KeyChooseMessage::TextboxInput)
+                .into()
+        }
+    }
+
     fn content(&self) -> Column<KeyChooseMessage> {
         let textbox = row![
             horizontal_space().width(Length::FillPortion(1)),
-            text_input("Input your key", &self.key)
-                .secure(true)
-                .width(Length::FillPortion(2))
-                .on_input(KeyChooseMessage::TextboxInput),
+            self.key_input(),
             horizontal_space().width(Length::FillPortion(1)),
         ];
 
+        let binary = checkbox("Binary mode", self.binary)
+            .on_toggle(KeyChooseMessage::BinaryToggled);
+
+        // Binary mode only has a byte-wise Vigenère.
+        let kind = (!self.binary).then(|| {
+            let choices = self.choices();
+            let selected = choices[self.selected];
+
+            row![
+                pick_list(
+                    choices,
+                    Some(selected),
+                    KeyChooseMessage::CipherSelected,
+                ),
+                widget::text(self.cipher_kind().description()),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center)
+        });
+
         let val = match &self.input {
             FileOrText::Text(text) if text.text().trim().is_empty() => {
                 Some(KeyChooseMessage::InFileChoose)
@@ -379,7 +563,7 @@ impl KeyChooseView {
 
         let buttons = match (&self.output_path, &self.input) {
             (Some(_), FileOrText::File(_) | FileOrText::Text(_))
-                if !self.key.is_empty() =>
+                if self.has_key() =>
             {
                 row![
                     button("Choose input file").on_press_maybe(val),
@@ -416,6 +600,7 @@ impl KeyChooseView {
         column![
             vertical_space().height(Length::FillPortion(8)),
             textbox,
+            row![binary].push_maybe(kind).spacing(10),
             vertical_space().height(Length::FillPortion(1)),
             big_textfield,
             buttons,
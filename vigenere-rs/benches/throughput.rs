@@ -0,0 +1,68 @@
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use std::hint::black_box;
+use vigenere_rs::{ByteVigenere, Foreign, Symbols, Vigenere};
+
+const TEXT_LEN: usize = 1 << 20;
+
+fn text() -> String {
+    "WEAREDISCOVEREDSAVEYOURSELF"
+        .chars()
+        .cycle()
+        .take(TEXT_LEN)
+        .collect()
+}
+
+fn latin(c: &mut Criterion) {
+    let vigenere = Vigenere::classical("LEMON".into(), Symbols::latin())
+        .expect("Vigenere couldn't be created")
+        .with_foreign(Foreign::Keep);
+    let text = text();
+
+    let mut group = c.benchmark_group("latin");
+    group.throughput(Throughput::Bytes(text.len() as u64));
+
+    group.bench_function("iterator", |b| {
+        b.iter(|| {
+            vigenere
+                .cipher(black_box(&text).chars())
+                .collect::<String>()
+        });
+    });
+
+    group.bench_function("cipher_str", |b| {
+        b.iter(|| vigenere.cipher_str(black_box(&text)));
+    });
+
+    group.bench_function("cipher_in_place", |b| {
+        let mut bytes = text.clone().into_bytes();
+        b.iter(|| vigenere.cipher_in_place(black_box(&mut bytes)));
+    });
+
+    group.finish();
+}
+
+fn bytes(c: &mut Criterion) {
+    let vigenere = ByteVigenere::new("LEMON").expect("Key is not empty");
+    let bytes = text().into_bytes();
+
+    let mut group = c.benchmark_group("bytes");
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+
+    group.bench_function("iterator", |b| {
+        b.iter(|| {
+            vigenere
+                .cipher(black_box(&bytes).iter().copied())
+                .collect::<Vec<u8>>()
+        });
+    });
+
+    group.bench_function("cipher_in_place", |b| {
+        let mut bytes = bytes.clone();
+        b.iter(|| vigenere.cipher_in_place(black_box(&mut bytes)));
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, latin, bytes);
+criterion_main!(benches);
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+
+const UTFGRAPHIC_START: u32 = 0x0020;
+const UTFGRAPHIC_ENDED: u32 = 0xFFFD;
+
+const UTFGRAPHIC_LEN: u32 = UTFGRAPHIC_ENDED - UTFGRAPHIC_START + 1;
+
+const SURROGATE_START: u32 = 0xD800;
+const SURROGATE_ENDED: u32 = 0xDFFF;
+
+const SURROGATE_LEN: u32 = SURROGATE_ENDED - SURROGATE_START + 1;
+const SCALAR_LEN: u32 = char::MAX as u32 + 1 - SURROGATE_LEN;
+
+const LATIN: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const RUSSIAN: &str = "АБВГДЕЁЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯ";
+const DIGITS: &str = "0123456789";
+
+/// Ordered set of symbols the cipher shifts around.
+///
+/// Indices run from `0` to `len() - 1`; `index_of` and `char_at` must be
+/// inverse to each other for every symbol of the alphabet.
+pub trait Alphabet {
+    fn len(&self) -> usize;
+
+    fn index_of(&self, ch: char) -> Option<usize>;
+
+    fn char_at(&self, index: usize) -> Option<char>;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn contains(&self, ch: char) -> bool {
+        self.index_of(ch).is_some()
+    }
+
+    fn lshift(&self, ch: char, shift: usize) -> Option<char> {
+        let len = self.len();
+        let index = self.index_of(ch)?;
+
+        self.char_at((index + shift % len) % len)
+    }
+
+    fn rshift(&self, ch: char, shift: usize) -> Option<char> {
+        let len = self.len();
+        let index = self.index_of(ch)?;
+
+        self.char_at((index + len - shift % len) % len)
+    }
+}
+
+impl<A: Alphabet + ?Sized> Alphabet for &A {
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    fn index_of(&self, ch: char) -> Option<usize> {
+        (**self).index_of(ch)
+    }
+
+    fn char_at(&self, index: usize) -> Option<char> {
+        (**self).char_at(index)
+    }
+}
+
+/// `U+0020..=U+FFFD`, the range the cipher has always worked over.
+///
+/// The range runs through the surrogate gap, so shifts that land there have
+/// no `char` and are lost. Use [`UnicodeScalar`] when that matters.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct UnicodeGraphic;
+
+impl Alphabet for UnicodeGraphic {
+    fn len(&self) -> usize {
+        UTFGRAPHIC_LEN as usize
+    }
+
+    fn index_of(&self, ch: char) -> Option<usize> {
+        let ch = ch as u32;
+
+        if !(UTFGRAPHIC_START..=UTFGRAPHIC_ENDED).contains(&ch) {
+            return None;
+        }
+
+        Some((ch - UTFGRAPHIC_START) as usize)
+    }
+
+    fn char_at(&self, index: usize) -> Option<char> {
+        let index = u32::try_from(index).ok()?;
+
+        if index >= UTFGRAPHIC_LEN {
+            return None;
+        }
+
+        char::from_u32(index + UTFGRAPHIC_START)
+    }
+}
+
+/// Every Unicode scalar value: `U+0000..=U+10FFFF` with the surrogate gap
+/// squeezed out, so any `&str` ciphers and deciphers back unchanged.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct UnicodeScalar;
+
+impl Alphabet for UnicodeScalar {
+    fn len(&self) -> usize {
+        SCALAR_LEN as usize
+    }
+
+    fn index_of(&self, ch: char) -> Option<usize> {
+        let ch = ch as u32;
+
+        if ch < SURROGATE_START {
+            Some(ch as usize)
+        } else {
+            Some((ch - SURROGATE_LEN) as usize)
+        }
+    }
+
+    fn char_at(&self, index: usize) -> Option<char> {
+        let index = u32::try_from(index).ok()?;
+
+        if index < SURROGATE_START {
+            char::from_u32(index)
+        } else {
+            char::from_u32(index.checked_add(SURROGATE_LEN)?)
+        }
+    }
+}
+
+/// Alphabet given by an explicit list of symbols.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbols {
+    chars: Vec<char>,
+    indices: HashMap<char, usize>,
+}
+
+impl Symbols {
+    /// Returns `None` if `symbols` is empty or has a repeated symbol.
+    pub fn new(symbols: &str) -> Option<Self> {
+        let chars = symbols.chars().collect::<Vec<_>>();
+        let mut indices = HashMap::with_capacity(chars.len());
+
+        for (index, &ch) in chars.iter().enumerate() {
+            if indices.insert(ch, index).is_some() {
+                return None;
+            }
+        }
+
+        if chars.is_empty() {
+            return None;
+        }
+
+        Some(Self { chars, indices })
+    }
+
+    /// `A..=Z`
+    #[must_use]
+    pub fn latin() -> Self {
+        Self::new(LATIN).expect("Latin alphabet is valid")
+    }
+
+    /// `А..=Я` with `Ё` after `Е`.
+    #[must_use]
+    pub fn russian() -> Self {
+        Self::new(RUSSIAN).expect("Russian alphabet is valid")
+    }
+
+    /// `0..=9`
+    #[must_use]
+    pub fn digits() -> Self {
+        Self::new(DIGITS).expect("Digits alphabet is valid")
+    }
+
+    /// Mixed alphabet: the distinct symbols of `keyword` in order, followed
+    /// by the rest of the alphabet. `None` if `keyword` has a symbol that is
+    /// not in the alphabet.
+    pub fn keyed(&self, keyword: &str) -> Option<Self> {
+        let mut symbols = String::with_capacity(self.chars.len());
+
+        for ch in keyword.chars().chain(self.chars.iter().copied()) {
+            if !self.indices.contains_key(&ch) {
+                return None;
+            }
+
+            if !symbols.contains(ch) {
+                symbols.push(ch);
+            }
+        }
+
+        Self::new(&symbols)
+    }
+
+    pub fn as_chars(&self) -> &[char] {
+        &self.chars
+    }
+}
+
+impl Alphabet for Symbols {
+    fn len(&self) -> usize {
+        self.chars.len()
+    }
+
+    fn index_of(&self, ch: char) -> Option<usize> {
+        self.indices.get(&ch).copied()
+    }
+
+    fn char_at(&self, index: usize) -> Option<char> {
+        self.chars.get(index).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn unicode_graphic_bounds() {
+        assert_eq!(UnicodeGraphic.index_of(' '), Some(0));
+        assert_eq!(UnicodeGraphic.index_of('\n'), None);
+        assert_eq!(UnicodeGraphic.char_at(0), Some(' '));
+        assert_eq!(UnicodeGraphic.char_at(UnicodeGraphic.len()), None);
+    }
+
+    #[test]
+    fn unicode_scalar_skips_surrogates() {
+        assert_eq!(UnicodeScalar.index_of('\u{D7FF}'), Some(0xD7FF));
+        assert_eq!(UnicodeScalar.index_of('\u{E000}'), Some(0xD800));
+        assert_eq!(UnicodeScalar.char_at(0xD800), Some('\u{E000}'));
+        assert_eq!(
+            UnicodeScalar.char_at(UnicodeScalar.len() - 1),
+            Some(char::MAX)
+        );
+        assert_eq!(UnicodeScalar.char_at(UnicodeScalar.len()), None);
+        assert_eq!(UnicodeScalar.lshift(char::MAX, 1), Some('\0'));
+    }
+
+    proptest! {
+        #[test]
+        fn unicode_scalar_is_bijective(ch: char) {
+            let index = UnicodeScalar.index_of(ch);
+
+            prop_assert!(
+                index.is_some_and(|index| index < UnicodeScalar.len())
+            );
+            prop_assert_eq!(
+                index.and_then(|index| UnicodeScalar.char_at(index)),
+                Some(ch)
+            );
+        }
+
+        #[test]
+        fn unicode_scalar_shift_roundtrip(ch: char, shift: usize) {
+            let shifted = UnicodeScalar.lshift(ch, shift);
+
+            prop_assert!(shifted.is_some());
+            prop_assert_eq!(
+                shifted.and_then(|ch| UnicodeScalar.rshift(ch, shift)),
+                Some(ch)
+            );
+        }
+    }
+
+    #[test]
+    fn symbols_presets() {
+        assert_eq!(Symbols::latin().len(), 26);
+        assert_eq!(Symbols::russian().len(), 33);
+        assert_eq!(Symbols::digits().len(), 10);
+
+        assert_eq!(Symbols::russian().index_of('Ё'), Some(6));
+        assert_eq!(Symbols::latin().lshift('Z', 1), Some('A'));
+        assert_eq!(Symbols::digits().rshift('0', 1), Some('9'));
+    }
+
+    #[test]
+    fn keyed_symbols() {
+        let keyed =
+            Symbols::latin().keyed("KRYPTOS").expect("Keyword is latin");
+        let keyed = keyed.as_chars().iter().collect::<String>();
+
+        assert_eq!(keyed, "KRYPTOSABCDEFGHIJLMNQUVWXZ");
+        assert!(Symbols::latin().keyed("KRYPTOS 1").is_none());
+    }
+
+    #[test]
+    fn symbols_rejects_duplicates() {
+        assert!(Symbols::new("ABCA").is_none());
+        assert!(Symbols::new("").is_none());
+    }
+}
@@ -0,0 +1,25 @@
+//! Cryptanalysis of periodic ciphers from the ciphertext alone.
+//!
+//! Everything here works on the symbols of an [`crate::Alphabet`], skipping
+//! the characters outside it the way [`crate::Foreign::Keep`] does, so
+//! positions count only the characters the key advances on.
+
+pub mod coincidence;
+pub mod kasiski;
+mod language;
+pub mod recovery;
+#[cfg(test)]
+mod samples;
+pub mod scoring;
+pub mod solver;
+
+pub use language::{LanguageModel, ModelError};
+
+use crate::Alphabet;
+
+/// Alphabet indices of the characters of `text` that are in `alphabet`.
+pub(crate) fn indices<A: Alphabet>(text: &str, alphabet: &A) -> Vec<usize> {
+    text.chars()
+        .filter_map(|ch| alphabet.index_of(ch))
+        .collect()
+}
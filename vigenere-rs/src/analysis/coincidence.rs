@@ -0,0 +1,162 @@
+//! Index of coincidence and the Friedman test. Text in a natural language
+//! repeats its common letters, so two symbols drawn from it match more often
+//! than two drawn at random; a periodic key smears that out unless the text
+//! is first split into the columns the key letters cover.
+
+use std::collections::HashMap;
+
+use super::{LanguageModel, indices};
+use crate::Alphabet;
+
+/// Chance that two symbols of `symbols` picked at random are the same.
+fn ioc(symbols: impl IntoIterator<Item = usize>) -> f64 {
+    let mut counts = HashMap::<usize, usize>::new();
+    let mut total = 0;
+
+    for symbol in symbols {
+        *counts.entry(symbol).or_default() += 1;
+        total += 1;
+    }
+
+    if total < 2 {
+        return 0.0;
+    }
+
+    let matches = counts.values().map(|n| n * (n - 1)).sum::<usize>();
+
+    matches as f64 / (total * (total - 1)) as f64
+}
+
+/// Index of coincidence of the symbols of `text` in `alphabet`, `0.0` when
+/// there are fewer than two.
+pub fn index_of_coincidence<A: Alphabet>(text: &str, alphabet: &A) -> f64 {
+    ioc(indices(text, alphabet))
+}
+
+/// Index of coincidence of each of the `period` columns `text` splits into
+/// when written in rows of `period` symbols.
+pub fn column_ioc<A: Alphabet>(
+    text: &str,
+    alphabet: &A,
+    period: usize,
+) -> Vec<f64> {
+    let symbols = indices(text, alphabet);
+
+    (0..period)
+        .map(|column| ioc(symbols.iter().copied().skip(column).step_by(period)))
+        .collect()
+}
+
+/// Average of [`column_ioc`], which comes close to the language's
+/// [`LanguageModel::expected_ioc`] at the key length and its multiples.
+pub fn mean_column_ioc<A: Alphabet>(
+    text: &str,
+    alphabet: &A,
+    period: usize,
+) -> f64 {
+    let columns = column_ioc(text, alphabet, period);
+
+    if columns.is_empty() {
+        return 0.0;
+    }
+
+    columns.iter().sum::<f64>() / columns.len() as f64
+}
+
+/// Friedman's estimate of the key length of a Vigenère ciphertext in the
+/// language of `model`, enciphered over `alphabet`. Rough on short texts,
+/// it tells the scale of the key more than its exact length.
+///
+/// `None` when the text is too short or shows no more coincidences than
+/// random symbols.
+pub fn friedman<A: Alphabet>(
+    ciphertext: &str,
+    alphabet: &A,
+    model: &LanguageModel,
+) -> Option<f64> {
+    let symbols = indices(ciphertext, alphabet);
+    let n = symbols.len() as f64;
+    let observed = ioc(symbols);
+    let expected = model.expected_ioc();
+    let random = 1.0 / alphabet.len() as f64;
+
+    let denominator = (n - 1.0) * observed - n * random + expected;
+    let length = n * (expected - random) / denominator;
+
+    (n >= 2.0 && denominator > 0.0 && length.is_finite()).then_some(length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Foreign, Symbols, UnicodeGraphic, Vigenere,
+        analysis::samples::{ENGLISH, RUSSIAN},
+    };
+
+    #[test]
+    fn plaintext_matches_model() {
+        let ioc =
+            index_of_coincidence(&ENGLISH.to_uppercase(), &Symbols::latin());
+        assert!((ioc - LanguageModel::english().expected_ioc()).abs() < 0.01);
+
+        let ioc =
+            index_of_coincidence(&RUSSIAN.to_uppercase(), &Symbols::russian());
+        assert!((ioc - LanguageModel::russian().expected_ioc()).abs() < 0.01);
+
+        assert_eq!(index_of_coincidence("A", &Symbols::latin()), 0.0);
+        assert_eq!(index_of_coincidence("AAAA", &Symbols::latin()), 1.0);
+    }
+
+    #[test]
+    fn columns_and_friedman() {
+        let latin = Symbols::latin();
+        let vigenere = Vigenere::classical("DICKENS".into(), latin.clone())
+            .expect("Vigenere couldn't be created");
+        let plaintext = ENGLISH.to_uppercase();
+        let ciphertext = vigenere.cipher(plaintext.chars()).collect::<String>();
+
+        let best = (1..=12)
+            .max_by(|&a, &b| {
+                mean_column_ioc(&ciphertext, &latin, a)
+                    .total_cmp(&mean_column_ioc(&ciphertext, &latin, b))
+            })
+            .expect("Periods are not empty");
+        assert_eq!(best, 7);
+        assert_eq!(column_ioc(&ciphertext, &latin, 7).len(), 7);
+
+        let estimate = friedman(&ciphertext, &latin, &LanguageModel::english())
+            .expect("Text is long enough");
+        assert!((4.0..=14.0).contains(&estimate), "{estimate}");
+    }
+
+    #[test]
+    fn cyrillic() {
+        let russian = Symbols::russian();
+        let vigenere = Vigenere::classical("ПУШКИН".into(), russian.clone())
+            .expect("Vigenere couldn't be created");
+        let plaintext = RUSSIAN.to_uppercase();
+        let ciphertext = vigenere.cipher(plaintext.chars()).collect::<String>();
+
+        let estimate =
+            friedman(&ciphertext, &russian, &LanguageModel::russian())
+                .expect("Text is long enough");
+        assert!((3.0..=12.0).contains(&estimate), "{estimate}");
+
+        // The app's own cipher: every printable character is a symbol and
+        // key characters shift by their code point.
+        let vigenere = Vigenere::new("Тестовый пароль".into())
+            .expect("Vigenere couldn't be created")
+            .with_foreign(Foreign::Keep);
+        let ciphertext = vigenere.cipher(RUSSIAN.chars()).collect::<String>();
+
+        let at_key = mean_column_ioc(&ciphertext, &UnicodeGraphic, 15);
+        for period in [7, 14, 16] {
+            assert!(
+                at_key
+                    > 2.0
+                        * mean_column_ioc(&ciphertext, &UnicodeGraphic, period)
+            );
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! Kasiski examination: repeated fragments of a ciphertext usually come
+//! from the same plaintext under the same part of the key, so the distances
+//! between them tend to be multiples of the key length.
+
+use std::collections::HashMap;
+
+use super::indices;
+use crate::Alphabet;
+
+/// Length of the fragments looked for by [`Kasiski::new`]. Shorter ones
+/// repeat by chance too often to tell anything.
+pub const DEFAULT_NGRAM_LEN: usize = 3;
+
+/// An n-gram found more than once in the ciphertext.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repetition {
+    pub ngram: String,
+    /// Where each occurrence starts, counting only alphabet symbols.
+    pub positions: Vec<usize>,
+}
+
+impl Repetition {
+    /// Distances between successive occurrences.
+    pub fn distances(&self) -> impl Iterator<Item = usize> + '_ {
+        self.positions.windows(2).map(|pair| pair[1] - pair[0])
+    }
+}
+
+/// A candidate key length and the number of distances it divides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyLength {
+    pub length: usize,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Kasiski {
+    repetitions: Vec<Repetition>,
+}
+
+impl Kasiski {
+    pub fn new<A: Alphabet>(ciphertext: &str, alphabet: &A) -> Self {
+        Self::with_ngram_len(ciphertext, alphabet, DEFAULT_NGRAM_LEN)
+    }
+
+    /// Looks for repeated n-grams of exactly `len` symbols.
+    pub fn with_ngram_len<A: Alphabet>(
+        ciphertext: &str,
+        alphabet: &A,
+        len: usize,
+    ) -> Self {
+        let symbols = indices(ciphertext, alphabet);
+        let mut seen = HashMap::<&[usize], Vec<usize>>::new();
+
+        if len > 0 {
+            for (position, ngram) in symbols.windows(len).enumerate() {
+                seen.entry(ngram).or_default().push(position);
+            }
+        }
+
+        let mut repetitions = seen
+            .into_iter()
+            .filter(|(_, positions)| positions.len() > 1)
+            .map(|(ngram, positions)| Repetition {
+                ngram: ngram
+                    .iter()
+                    .filter_map(|&index| alphabet.char_at(index))
+                    .collect(),
+                positions,
+            })
+            .collect::<Vec<_>>();
+        repetitions.sort_by_key(|repetition| repetition.positions[0]);
+
+        Self { repetitions }
+    }
+
+    /// Repeated n-grams in order of first appearance.
+    pub fn repetitions(&self) -> &[Repetition] {
+        &self.repetitions
+    }
+
+    /// Distances between successive occurrences of every repeated n-gram.
+    pub fn distances(&self) -> Vec<usize> {
+        self.repetitions
+            .iter()
+            .flat_map(Repetition::distances)
+            .collect()
+    }
+
+    /// Key lengths from 2 to `max_len` that divide at least one distance,
+    /// those dividing the most first and shorter ones first among equals.
+    ///
+    /// Multiples of the real key length divide fewer distances than it
+    /// does, but its factors divide more, so the real length is often the
+    /// longest of the leading few rather than the very first.
+    pub fn key_lengths(&self, max_len: usize) -> Vec<KeyLength> {
+        let distances = self.distances();
+
+        let mut lengths = (2..=max_len)
+            .map(|length| KeyLength {
+                length,
+                count: distances
+                    .iter()
+                    .filter(|&&distance| distance % length == 0)
+                    .count(),
+            })
+            .filter(|candidate| candidate.count > 0)
+            .collect::<Vec<_>>();
+        lengths.sort_by(|a, b| {
+            b.count.cmp(&a.count).then(a.length.cmp(&b.length))
+        });
+
+        lengths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Symbols, Vigenere};
+
+    #[test]
+    fn finds_repetitions() {
+        let kasiski = Kasiski::new("ABC xyz-ABC QQ ABCD", &Symbols::latin());
+
+        assert_eq!(
+            kasiski.repetitions(),
+            [Repetition {
+                ngram: "ABC".into(),
+                positions: vec![0, 3, 8],
+            }]
+        );
+        assert_eq!(kasiski.distances(), [3, 5]);
+        assert_eq!(
+            kasiski.key_lengths(5),
+            [
+                KeyLength {
+                    length: 3,
+                    count: 1
+                },
+                KeyLength {
+                    length: 5,
+                    count: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ranks_key_length() {
+        let plaintext = "THEREISNOTHINGMOREDECEPTIVETHANANOBVIOUSFACT\
+            THEWORLDISFULLOFOBVIOUSTHINGSWHICHNOBODYBYANYCHANCEEVEROBSERVES\
+            YOUKNOWMYMETHODITISFOUNDEDUPONTHEOBSERVATIONOFTRIFLES\
+            WHENYOUHAVEELIMINATEDTHEIMPOSSIBLEWHATEVERREMAINSHOWEVER\
+            IMPROBABLEMUSTBETHETRUTH";
+        let vigenere = Vigenere::classical("HOLMES".into(), Symbols::latin())
+            .expect("Vigenere couldn't be created");
+        let ciphertext = vigenere.cipher(plaintext.chars()).collect::<String>();
+
+        let kasiski = Kasiski::new(&ciphertext, &Symbols::latin());
+        let lengths = kasiski.key_lengths(20);
+
+        assert!(lengths[..3].iter().any(|candidate| candidate.length == 6));
+        assert!(kasiski.distances().iter().all(|distance| distance % 6 == 0));
+    }
+
+    #[test]
+    fn cyrillic_text() {
+        let vigenere = Vigenere::classical("КЛЮЧ".into(), Symbols::russian())
+            .expect("Vigenere couldn't be created");
+        let ciphertext = vigenere
+            .cipher("ШИФРШИФРОВАНИЕШИФРШИФР".chars())
+            .collect::<String>();
+
+        let kasiski = Kasiski::new(&ciphertext, &Symbols::russian());
+        let distances = kasiski.distances();
+        assert!(!distances.is_empty());
+        assert!(distances.iter().all(|distance| distance % 4 == 0));
+    }
+}
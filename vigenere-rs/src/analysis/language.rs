@@ -0,0 +1,529 @@
+use std::{collections::HashMap, fmt, io, path::Path, str::FromStr};
+
+use crate::{Alphabet, Symbols};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ModelError {
+    #[error("Couldn't read the model: {0}")]
+    Io(#[from] io::Error),
+    #[error("Malformed model at line {line}")]
+    Parse { line: usize },
+    #[error("No letters of the alphabet to learn from")]
+    Empty,
+    #[error("Symbol {0:?} can't be saved, it would start a comment or a line")]
+    Symbol(char),
+}
+
+/// Lengths of the n-grams a model keeps counts of, besides single letters.
+const ORDERS: [usize; 2] = [2, 4];
+
+/// Public-domain texts the built-in n-gram statistics are learned from.
+const ENGLISH_CORPUS: &str = include_str!("../../data/english.txt");
+const RUSSIAN_CORPUS: &str = include_str!("../../data/russian.txt");
+
+/// Letter frequencies of English in percent, `A..=Z`.
+const ENGLISH: [f64; 26] = [
+    8.167, 1.492, 2.782, 4.253, 12.702, 2.228, 2.015, 6.094, 6.966, 0.153,
+    0.772, 4.025, 2.406, 6.749, 7.507, 1.929, 0.095, 5.987, 6.327, 9.056,
+    2.758, 0.978, 2.360, 0.150, 1.974, 0.074,
+];
+
+/// Letter frequencies of Russian in percent, `А..=Я` with `Ё` after `Е`.
+/// `Ё` is rare because most texts write `Е` for it.
+const RUSSIAN: [f64; 33] = [
+    8.01, 1.59, 4.54, 1.70, 2.98, 8.45, 0.04, 0.94, 1.65, 7.35, 1.21, 3.49,
+    4.40, 3.21, 6.70, 10.97, 2.81, 4.73, 5.47, 6.26, 2.62, 0.26, 0.97, 0.48,
+    1.44, 0.73, 0.36, 0.04, 1.90, 1.74, 0.32, 0.64, 2.01,
+];
+
+/// Statistics of a language over its alphabet, which the analysis
+/// compares ciphertexts against.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageModel {
+    alphabet: Symbols,
+    letters: Vec<f64>,
+    bigrams: NGrams,
+    quadgrams: NGrams,
+}
+
+/// Counts of the n-grams of one length, keyed by their symbol indices read
+/// as a number in base alphabet length.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct NGrams {
+    counts: HashMap<u64, f64>,
+    log_probs: HashMap<u64, f64>,
+    floor: f64,
+}
+
+impl NGrams {
+    fn new(counts: HashMap<u64, f64>) -> Self {
+        if counts.is_empty() {
+            return Self::default();
+        }
+
+        let total = counts.values().sum::<f64>();
+        let log_probs = counts
+            .iter()
+            .map(|(&ngram, &count)| (ngram, (count / total).ln()))
+            .collect();
+
+        Self {
+            counts,
+            log_probs,
+            // An unseen n-gram is taken to be a good deal rarer than any
+            // seen once.
+            floor: (0.01 / total).ln(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+}
+
+impl LanguageModel {
+    /// English over [`Symbols::latin`].
+    #[must_use]
+    pub fn english() -> Self {
+        Self::builtin(Symbols::latin(), &ENGLISH, ENGLISH_CORPUS)
+    }
+
+    /// Russian over [`Symbols::russian`].
+    #[must_use]
+    pub fn russian() -> Self {
+        Self::builtin(Symbols::russian(), &RUSSIAN, RUSSIAN_CORPUS)
+    }
+
+    /// Published letter table, with the n-grams of the bundled corpus.
+    fn builtin(alphabet: Symbols, letters: &[f64], corpus: &str) -> Self {
+        let learned =
+            Self::learn(alphabet, corpus).expect("Corpus has letters");
+
+        Self {
+            letters: normalized(letters)
+                .expect("Letter table matches the alphabet"),
+            ..learned
+        }
+    }
+
+    /// Model from letter frequencies in alphabet order, in any unit. `None`
+    /// when there is not one non-negative frequency per symbol or they are
+    /// all zero.
+    pub fn from_frequencies(
+        alphabet: Symbols,
+        frequencies: &[f64],
+    ) -> Option<Self> {
+        if frequencies.len() != alphabet.len() {
+            return None;
+        }
+
+        Some(Self {
+            alphabet,
+            letters: normalized(frequencies)?,
+            bigrams: NGrams::default(),
+            quadgrams: NGrams::default(),
+        })
+    }
+
+    /// Model learned from the letters, bigrams and quadgrams of `corpus`.
+    /// Characters are looked up as they are and then upper-cased, the rest
+    /// are skipped, so n-grams run across spaces and punctuation. `None`
+    /// when the corpus has no letters of the alphabet.
+    pub fn learn(alphabet: Symbols, corpus: &str) -> Option<Self> {
+        let symbols = corpus
+            .chars()
+            .filter_map(|ch| symbol(&alphabet, ch))
+            .collect::<Vec<_>>();
+        let mut letters = vec![0.0; alphabet.len()];
+
+        for &symbol in &symbols {
+            letters[symbol] += 1.0;
+        }
+
+        let [bigrams, quadgrams] = ORDERS.map(|order| {
+            let mut counts = HashMap::new();
+
+            for ngram in symbols.windows(order) {
+                *counts.entry(key(&alphabet, ngram)).or_default() += 1.0;
+            }
+
+            NGrams::new(counts)
+        });
+
+        Some(Self {
+            letters: normalized(&letters)?,
+            alphabet,
+            bigrams,
+            quadgrams,
+        })
+    }
+
+    /// [`LanguageModel::learn`] from the UTF-8 text in the file at `path`.
+    pub fn from_corpus_file(
+        alphabet: Symbols,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, ModelError> {
+        let corpus = std::fs::read_to_string(path)?;
+
+        Self::learn(alphabet, &corpus).ok_or(ModelError::Empty)
+    }
+
+    /// Reads a model written by [`LanguageModel::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ModelError> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// Writes the model in its text format: an `alphabet` line with the
+    /// symbols, then one `<n-gram> <count>` line per letter, bigram and
+    /// quadgram. Lines starting with `#` are comments, so alphabets with
+    /// `#` or line breaks are refused with [`ModelError::Symbol`].
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ModelError> {
+        if let Some(&ch) = self
+            .alphabet
+            .as_chars()
+            .iter()
+            .find(|&&ch| matches!(ch, '#' | '\n' | '\r'))
+        {
+            return Err(ModelError::Symbol(ch));
+        }
+
+        Ok(std::fs::write(path, self.to_string())?)
+    }
+
+    pub const fn alphabet(&self) -> &Symbols {
+        &self.alphabet
+    }
+
+    /// Probability of each symbol, in alphabet order, adding up to 1.
+    pub fn letter_frequencies(&self) -> &[f64] {
+        &self.letters
+    }
+
+    /// Chance that two symbols picked at random from a text in the language
+    /// are the same.
+    pub fn expected_ioc(&self) -> f64 {
+        self.letters.iter().map(|p| p * p).sum()
+    }
+
+    /// Natural log of the probability of the n-gram of alphabet indices
+    /// `ngram`, with a floor for those never seen. `None` when the model
+    /// has no statistics for n-grams of that length.
+    pub fn ngram_log_prob(&self, ngram: &[usize]) -> Option<f64> {
+        if ngram.iter().any(|&symbol| symbol >= self.alphabet.len()) {
+            return None;
+        }
+
+        let ngrams = match ngram.len() {
+            1 => {
+                return Some(
+                    self.letters[ngram[0]].max(f64::MIN_POSITIVE).ln(),
+                );
+            }
+            2 => &self.bigrams,
+            4 => &self.quadgrams,
+            _ => return None,
+        };
+
+        if ngrams.is_empty() {
+            return None;
+        }
+
+        Some(
+            ngrams
+                .log_probs
+                .get(&key(&self.alphabet, ngram))
+                .copied()
+                .unwrap_or(ngrams.floor),
+        )
+    }
+
+    /// Log-probabilities of every n-gram of length `order`, indexed by its
+    /// symbols read as a number in base alphabet length. `None` like
+    /// [`LanguageModel::ngram_log_prob`].
+    pub(crate) fn ngram_log_probs(&self, order: usize) -> Option<Vec<f64>> {
+        let ngrams = match order {
+            1 => {
+                return Some(
+                    self.letters
+                        .iter()
+                        .map(|letter| letter.max(f64::MIN_POSITIVE).ln())
+                        .collect(),
+                );
+            }
+            2 => &self.bigrams,
+            4 => &self.quadgrams,
+            _ => return None,
+        };
+
+        if ngrams.is_empty() {
+            return None;
+        }
+
+        let size = self
+            .alphabet
+            .len()
+            .checked_pow(u32::try_from(order).ok()?)?;
+        let mut log_probs = vec![ngrams.floor; size];
+        for (&ngram, &log_prob) in &ngrams.log_probs {
+            log_probs[usize::try_from(ngram).ok()?] = log_prob;
+        }
+
+        Some(log_probs)
+    }
+}
+
+impl fmt::Display for LanguageModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "alphabet {}",
+            self.alphabet.as_chars().iter().collect::<String>()
+        )?;
+
+        for (&ch, frequency) in
+            self.alphabet.as_chars().iter().zip(&self.letters)
+        {
+            writeln!(f, "{ch} {frequency}")?;
+        }
+
+        for (order, ngrams) in
+            ORDERS.into_iter().zip([&self.bigrams, &self.quadgrams])
+        {
+            let mut counts = ngrams.counts.iter().collect::<Vec<_>>();
+            counts.sort_unstable_by_key(|&(&ngram, _)| ngram);
+
+            for (&ngram, count) in counts {
+                let ngram = unkey(&self.alphabet, ngram, order);
+                writeln!(f, "{ngram} {count}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for LanguageModel {
+    type Err = ModelError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line))
+            .filter(|(_, line)| {
+                !line.starts_with('#') && !line.trim().is_empty()
+            });
+
+        let (line, header) = lines.next().ok_or(ModelError::Empty)?;
+        let alphabet = header
+            .strip_prefix("alphabet ")
+            .and_then(Symbols::new)
+            .ok_or(ModelError::Parse { line })?;
+
+        let mut letters = vec![0.0; alphabet.len()];
+        let mut counts = ORDERS.map(|_| HashMap::new());
+
+        for (line, text) in lines {
+            let parse = || {
+                let (ngram, count) = text.rsplit_once(' ')?;
+                let count =
+                    count.parse::<f64>().ok().filter(|count| *count >= 0.0)?;
+                let ngram = ngram
+                    .chars()
+                    .map(|ch| alphabet.index_of(ch))
+                    .collect::<Option<Vec<_>>>()?;
+
+                Some((ngram, count))
+            };
+            let (ngram, count) = parse().ok_or(ModelError::Parse { line })?;
+
+            match ngram.len() {
+                1 => letters[ngram[0]] = count,
+                len => {
+                    let order = ORDERS
+                        .iter()
+                        .position(|&order| order == len)
+                        .ok_or(ModelError::Parse { line })?;
+                    if count > 0.0 {
+                        counts[order].insert(key(&alphabet, &ngram), count);
+                    }
+                }
+            }
+        }
+
+        let [bigrams, quadgrams] = counts.map(NGrams::new);
+
+        Ok(Self {
+            letters: normalized(&letters).ok_or(ModelError::Empty)?,
+            alphabet,
+            bigrams,
+            quadgrams,
+        })
+    }
+}
+
+/// `frequencies` scaled to add up to 1. `None` when one is negative or
+/// they are all zero.
+fn normalized(frequencies: &[f64]) -> Option<Vec<f64>> {
+    let total = frequencies.iter().sum::<f64>();
+
+    if frequencies.iter().any(|&frequency| frequency < 0.0) || total <= 0.0 {
+        return None;
+    }
+
+    // Leave probabilities alone, so saved models read back unchanged
+    // instead of off by a rounding error.
+    if (total - 1.0).abs() < 1e-9 {
+        return Some(frequencies.to_vec());
+    }
+
+    Some(frequencies.iter().map(|count| count / total).collect())
+}
+
+/// Alphabet index of `ch`, or of its upper case.
+fn symbol(alphabet: &Symbols, ch: char) -> Option<usize> {
+    alphabet.index_of(ch).or_else(|| {
+        let mut upper = ch.to_uppercase();
+
+        match (upper.next(), upper.next()) {
+            (Some(upper), None) => alphabet.index_of(upper),
+            _ => None,
+        }
+    })
+}
+
+fn key(alphabet: &Symbols, ngram: &[usize]) -> u64 {
+    let len = alphabet.len() as u64;
+
+    ngram
+        .iter()
+        .fold(0, |key, &symbol| key * len + symbol as u64)
+}
+
+fn unkey(alphabet: &Symbols, mut key: u64, order: usize) -> String {
+    let len = alphabet.len() as u64;
+    let mut ngram = (0..order)
+        .map(|_| {
+            let symbol = (key % len) as usize;
+            key /= len;
+
+            alphabet.as_chars()[symbol]
+        })
+        .collect::<Vec<_>>();
+    ngram.reverse();
+
+    ngram.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_models() {
+        for (model, ioc) in [
+            (LanguageModel::english(), 0.0655),
+            (LanguageModel::russian(), 0.0553),
+        ] {
+            let total = model.letter_frequencies().iter().sum::<f64>();
+            assert!((total - 1.0).abs() < 1e-9);
+            assert!((model.expected_ioc() - ioc).abs() < 0.001);
+        }
+
+        assert!(
+            LanguageModel::from_frequencies(Symbols::digits(), &[1.0])
+                .is_none()
+        );
+    }
+
+    fn log_prob(model: &LanguageModel, ngram: &str) -> Option<f64> {
+        let ngram = ngram
+            .chars()
+            .map(|ch| model.alphabet().index_of(ch))
+            .collect::<Option<Vec<_>>>()?;
+
+        model.ngram_log_prob(&ngram)
+    }
+
+    #[test]
+    fn builtin_ngrams() {
+        let english = LanguageModel::english();
+        assert!(log_prob(&english, "TH") > log_prob(&english, "QZ"));
+        assert!(log_prob(&english, "TION") > log_prob(&english, "XQJZ"));
+        assert_eq!(log_prob(&english, "THE"), None);
+
+        let russian = LanguageModel::russian();
+        assert!(log_prob(&russian, "СТВО") > log_prob(&russian, "ЪЫЬЙ"));
+    }
+
+    #[test]
+    fn samples_are_held_out() {
+        use crate::analysis::samples::{ENGLISH, RUSSIAN};
+
+        for (corpus, sample) in
+            [(ENGLISH_CORPUS, ENGLISH), (RUSSIAN_CORPUS, RUSSIAN)]
+        {
+            let opening = sample.split(',').next().expect("Sample has text");
+            assert!(!corpus.contains(opening), "{opening}");
+        }
+    }
+
+    #[test]
+    fn learns_from_corpus() {
+        let model = LanguageModel::learn(Symbols::latin(), "Abba, abba!")
+            .expect("Corpus has letters");
+
+        assert_eq!(model.letter_frequencies()[..3], [0.5, 0.5, 0.0]);
+        // ABBAABBA: AB, BB, BA, AA, AB, BB, BA.
+        let ab = log_prob(&model, "AB").expect("Model has bigrams");
+        assert!((ab - (2.0_f64 / 7.0).ln()).abs() < 1e-12);
+        assert!(log_prob(&model, "BAAB") > log_prob(&model, "AAAA"));
+
+        assert!(LanguageModel::learn(Symbols::latin(), "123").is_none());
+    }
+
+    #[test]
+    fn save_and_load() {
+        let russian = LanguageModel::russian();
+        let path = std::env::temp_dir()
+            .join(format!("vigenere-rs-russian-{}.model", std::process::id()));
+
+        russian.save(&path).expect("Model is saved");
+        let loaded = LanguageModel::load(&path).expect("Model is loaded");
+        std::fs::remove_file(&path).ok();
+        assert_eq!(loaded, russian);
+
+        let frequencies = LanguageModel::from_frequencies(
+            Symbols::digits(),
+            &[1.0, 2.0, 3.0, 4.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+        )
+        .expect("One frequency per digit");
+        let text = format!("# Digits\n\n{frequencies}");
+        assert_eq!(text.parse::<LanguageModel>().ok(), Some(frequencies));
+
+        assert!(matches!(
+            "alphabet AB\nA 1\nAC 2".parse::<LanguageModel>(),
+            Err(ModelError::Parse { line: 3 })
+        ));
+        assert!(matches!(
+            "alphabet AB\nABC 2".parse::<LanguageModel>(),
+            Err(ModelError::Parse { line: 2 })
+        ));
+        assert!(matches!(
+            "alphabet AB\nAB 2".parse::<LanguageModel>(),
+            Err(ModelError::Empty)
+        ));
+    }
+
+    #[test]
+    fn comment_symbol_is_not_saved() {
+        let alphabet = Symbols::new("#AB").expect("Symbols are distinct");
+        let model = LanguageModel::learn(alphabet, "#AB#BA##")
+            .expect("Corpus has letters");
+        let path = std::env::temp_dir()
+            .join(format!("vigenere-rs-comment-{}.model", std::process::id()));
+
+        assert!(matches!(model.save(&path), Err(ModelError::Symbol('#'))));
+        assert!(!path.exists());
+    }
+}
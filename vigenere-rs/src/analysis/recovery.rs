@@ -0,0 +1,392 @@
+//! Vigenère key recovery from the ciphertext alone: guess the period from
+//! the index of coincidence, then solve every column as a Caesar cipher by
+//! picking the shift whose plaintext letters fit the language best.
+//!
+//! The textbook cipher over the model's alphabet is the default. Other
+//! alphabets and key mappings are solved by trying every character the key
+//! may be made of, see [`KeyRecovery::with_cipher`].
+
+use std::collections::HashSet;
+
+use super::{
+    LanguageModel, coincidence::mean_column_ioc, indices,
+    scoring::chi_squared_strays,
+};
+use crate::{Alphabet, Foreign, KeyMapping, Symbols, Vigenere};
+
+/// A key the solver came up with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// Made of the characters keys are tried with, the model's alphabet
+    /// unless [`KeyRecovery::with_cipher`] says otherwise.
+    pub key: String,
+    pub plaintext: String,
+    /// Chi-squared distance of the plaintext from the language, lower is
+    /// better.
+    pub score: f64,
+}
+
+/// Settings of the solver, which works on textbook Vigenère over the
+/// alphabet of its language model unless told otherwise. Characters outside
+/// the cipher alphabet are passed through, so upper-case the ciphertext
+/// first if the model is and the cipher is textbook.
+#[derive(Debug, Clone)]
+pub struct KeyRecovery<'a, A: Alphabet = Symbols> {
+    model: &'a LanguageModel,
+    alphabet: A,
+    mapping: KeyMapping,
+    key_chars: Vec<char>,
+    max_period: usize,
+    periods: usize,
+    candidates: usize,
+}
+
+impl<'a> KeyRecovery<'a> {
+    pub fn new(model: &'a LanguageModel) -> Self {
+        let alphabet = model.alphabet().clone();
+        let key_chars = (0..alphabet.len())
+            .filter_map(|index| alphabet.char_at(index))
+            .collect();
+
+        Self {
+            model,
+            alphabet,
+            mapping: KeyMapping::Index,
+            key_chars,
+            max_period: 20,
+            periods: 3,
+            candidates: 5,
+        }
+    }
+}
+
+impl<'a, A: Alphabet + Clone> KeyRecovery<'a, A> {
+    /// Solves ciphertexts of a [`Vigenere`] over `alphabet` with `mapping`,
+    /// whose keys are made of `key_chars`. What the app ciphers by default
+    /// is [`crate::UnicodeGraphic`] with [`KeyMapping::CodePoint`] and keys
+    /// of ASCII letters.
+    ///
+    /// Plaintext letters count in either case. Deciphered characters that
+    /// are neither letters of the model nor ASCII can't be plaintext, which
+    /// is what tells a key character from the same one in the other case.
+    /// Characters the cipher dropped put the columns out of step, so the
+    /// plaintext has to be all in `alphabet` or ciphered with
+    /// [`Foreign::Keep`].
+    #[must_use]
+    pub fn with_cipher<B: Alphabet>(
+        self,
+        alphabet: B,
+        mapping: KeyMapping,
+        key_chars: impl IntoIterator<Item = char>,
+    ) -> KeyRecovery<'a, B> {
+        KeyRecovery {
+            model: self.model,
+            alphabet,
+            mapping,
+            key_chars: key_chars.into_iter().collect(),
+            max_period: self.max_period,
+            periods: self.periods,
+            candidates: self.candidates,
+        }
+    }
+
+    /// Longest key length tried, 20 by default.
+    #[must_use]
+    pub const fn with_max_period(mut self, max_period: usize) -> Self {
+        self.max_period = max_period;
+        self
+    }
+
+    /// How many of the likeliest periods get solved, 3 by default.
+    #[must_use]
+    pub const fn with_periods(mut self, periods: usize) -> Self {
+        self.periods = periods;
+        self
+    }
+
+    /// Most candidates returned, 5 by default.
+    #[must_use]
+    pub const fn with_candidates(mut self, candidates: usize) -> Self {
+        self.candidates = candidates;
+        self
+    }
+
+    /// Key lengths from 1 to the maximum, likeliest first.
+    ///
+    /// Multiples of the key length look just as much like the language, and
+    /// their narrower columns let the solver overfit, so the shortest
+    /// periods whose columns read closer to the language than to random
+    /// symbols come first. Each group is in order of how close its index of
+    /// coincidence is to the language's.
+    pub fn periods(&self, ciphertext: &str) -> Vec<usize> {
+        let alphabet = &self.alphabet;
+        let expected = self.model.expected_ioc();
+        let random = 1.0 / alphabet.len() as f64;
+        // Columns need a couple of symbols each to say anything.
+        let max_period = self
+            .max_period
+            .min(indices(ciphertext, alphabet).len() / 2)
+            .max(1);
+
+        let iocs = (1..=max_period)
+            .map(|period| {
+                (period, mean_column_ioc(ciphertext, alphabet, period))
+            })
+            .collect::<Vec<_>>();
+        let language_like = |ioc: f64| ioc >= f64::midpoint(expected, random);
+        let has_shorter = |period: usize| {
+            iocs.iter().any(|&(shorter, ioc)| {
+                shorter < period
+                    && period.is_multiple_of(shorter)
+                    && language_like(ioc)
+            })
+        };
+
+        let mut periods = iocs
+            .iter()
+            .map(|&(period, ioc)| {
+                let shortest = language_like(ioc) && !has_shorter(period);
+
+                (period, !shortest, (ioc - expected).abs())
+            })
+            .collect::<Vec<_>>();
+        periods.sort_by(|a, b| {
+            a.1.cmp(&b.1).then(a.2.total_cmp(&b.2)).then(a.0.cmp(&b.0))
+        });
+
+        periods.into_iter().map(|(period, ..)| period).collect()
+    }
+
+    /// Candidate keys with their plaintexts, those for the likeliest period
+    /// first and by score among them. Scores of different periods don't
+    /// compare well, as longer keys can fit any text more closely.
+    pub fn recover(&self, ciphertext: &str) -> Vec<Candidate> {
+        let symbols = ciphertext
+            .chars()
+            .filter(|&ch| self.alphabet.contains(ch))
+            .collect::<Vec<_>>();
+        let mut seen = HashSet::new();
+
+        let mut candidates = Vec::new();
+        for period in self.periods(ciphertext).into_iter().take(self.periods) {
+            let mut found = self
+                .keys(&symbols, period)
+                .into_iter()
+                .map(|key| shortest_period(&key).to_vec())
+                .filter(|key| seen.insert(key.clone()))
+                .filter_map(|key| self.candidate(ciphertext, &key))
+                .collect::<Vec<_>>();
+            found.sort_by(|a, b| a.score.total_cmp(&b.score));
+
+            candidates.extend(found);
+        }
+        candidates.truncate(self.candidates);
+
+        candidates
+    }
+
+    /// The best key for `period`, then the same key with the runner-up
+    /// character in each column, closest calls first. Keys are indices
+    /// into the key characters.
+    fn keys(&self, symbols: &[char], period: usize) -> Vec<Vec<usize>> {
+        let columns = (0..period)
+            .map(|column| {
+                let column = symbols
+                    .iter()
+                    .copied()
+                    .skip(column)
+                    .step_by(period)
+                    .collect::<Vec<_>>();
+
+                self.rank_keys(&column)
+            })
+            .collect::<Vec<_>>();
+
+        let Some(best) = columns
+            .iter()
+            .map(|ranked| ranked.first().map(|&(index, _)| index))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return Vec::new();
+        };
+
+        let mut runners_up = columns
+            .iter()
+            .enumerate()
+            .filter(|(_, ranked)| ranked.len() > 1)
+            .map(|(column, ranked)| {
+                (column, ranked[1].0, ranked[1].1 - ranked[0].1)
+            })
+            .collect::<Vec<_>>();
+        runners_up.sort_by(|a, b| a.2.total_cmp(&b.2));
+
+        let alternatives = runners_up.into_iter().map(|(column, index, _)| {
+            let mut key = best.clone();
+            key[column] = index;
+            key
+        });
+
+        std::iter::once(best.clone())
+            .chain(alternatives.take(self.candidates))
+            .collect()
+    }
+
+    /// Every key character for a column with the chi-squared of what it
+    /// deciphers to, best first.
+    fn rank_keys(&self, column: &[char]) -> Vec<(usize, f64)> {
+        let mut ranked = self
+            .key_chars
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &key)| {
+                let vigenere = self.vigenere(key.to_string())?;
+                let plaintext = vigenere.decipher(column.iter().copied());
+
+                Some((index, self.score(plaintext)))
+            })
+            .collect::<Vec<_>>();
+        ranked.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+
+        ranked
+    }
+
+    /// Chi-squared of deciphered characters against the model, letters in
+    /// either case and anything else but ASCII counted as a stray.
+    fn score(&self, plaintext: impl Iterator<Item = char>) -> f64 {
+        let alphabet = self.model.alphabet();
+        let mut counts = vec![0; alphabet.len()];
+        let mut strays = 0;
+
+        for ch in plaintext {
+            let mut upper = ch.to_uppercase();
+            let index = alphabet.index_of(ch).or_else(|| {
+                upper
+                    .next()
+                    .filter(|_| upper.len() == 0)
+                    .and_then(|upper| alphabet.index_of(upper))
+            });
+
+            match index {
+                Some(index) => counts[index] += 1,
+                None if ch.is_ascii() => {}
+                None => strays += 1,
+            }
+        }
+
+        chi_squared_strays(&counts, strays, self.model)
+    }
+
+    fn vigenere(&self, key: String) -> Option<Vigenere<A>> {
+        let vigenere = Vigenere::with_alphabet(key, self.alphabet.clone())
+            .ok()?
+            .with_key_mapping(self.mapping)
+            .with_foreign(Foreign::Keep);
+
+        Some(vigenere)
+    }
+
+    fn candidate(&self, ciphertext: &str, key: &[usize]) -> Option<Candidate> {
+        let key = key
+            .iter()
+            .map(|&index| self.key_chars.get(index))
+            .collect::<Option<String>>()?;
+
+        let plaintext = self
+            .vigenere(key.clone())?
+            .decipher(ciphertext.chars())
+            .collect::<String>();
+        let deciphered =
+            plaintext.chars().filter(|&ch| self.alphabet.contains(ch));
+
+        Some(Candidate {
+            key,
+            score: self.score(deciphered),
+            plaintext,
+        })
+    }
+}
+
+/// `shifts` cut down to one repeat, so `LEMONLEMON` and `LEMON` are found
+/// only once.
+fn shortest_period<T: PartialEq>(shifts: &[T]) -> &[T] {
+    let len = shifts.len();
+
+    (1..len)
+        .filter(|&period| len.is_multiple_of(period))
+        .find(|&period| {
+            shifts
+                .iter()
+                .enumerate()
+                .all(|(index, shift)| *shift == shifts[index % period])
+        })
+        .map_or(shifts, |period| &shifts[..period])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        UnicodeGraphic,
+        analysis::samples::{ENGLISH, RUSSIAN},
+    };
+
+    fn recover(
+        plaintext: &str,
+        key: &str,
+        alphabet: Symbols,
+        model: &LanguageModel,
+    ) -> Vec<Candidate> {
+        let vigenere = Vigenere::classical(key.into(), alphabet)
+            .expect("Vigenere couldn't be created")
+            .with_foreign(Foreign::Keep);
+        let ciphertext = vigenere.cipher(plaintext.chars()).collect::<String>();
+
+        KeyRecovery::new(model).recover(&ciphertext)
+    }
+
+    #[test]
+    fn recovers_english_key() {
+        let plaintext = ENGLISH.to_uppercase();
+        let english = LanguageModel::english();
+        let candidates =
+            recover(&plaintext, "DICKENS", Symbols::latin(), &english);
+
+        assert_eq!(candidates[0].key, "DICKENS");
+        assert_eq!(candidates[0].plaintext, plaintext);
+        assert!(candidates.len() > 1);
+        assert!(candidates[1].score >= candidates[0].score);
+    }
+
+    #[test]
+    fn recovers_russian_key() {
+        let plaintext = RUSSIAN.to_uppercase();
+        let russian = LanguageModel::russian();
+        let candidates =
+            recover(&plaintext, "ПУШКИН", Symbols::russian(), &russian);
+
+        assert_eq!(candidates[0].key, "ПУШКИН");
+        assert_eq!(candidates[0].plaintext, plaintext);
+    }
+
+    #[test]
+    fn recovers_app_key() {
+        let english = LanguageModel::english();
+        let vigenere = Vigenere::new("Dickens".into())
+            .expect("Vigenere couldn't be created");
+        let ciphertext = vigenere.cipher(ENGLISH.chars()).collect::<String>();
+
+        let letters = ('A'..='Z').chain('a'..='z');
+        let candidates = KeyRecovery::new(&english)
+            .with_cipher(UnicodeGraphic, KeyMapping::CodePoint, letters)
+            .recover(&ciphertext);
+
+        assert_eq!(candidates[0].key, "Dickens");
+        assert_eq!(candidates[0].plaintext, ENGLISH);
+    }
+
+    #[test]
+    fn repeated_keys_are_cut_down() {
+        assert_eq!(shortest_period(&[1, 2, 1, 2, 1, 2]), [1, 2]);
+        assert_eq!(shortest_period(&[1, 2, 3]), [1, 2, 3]);
+    }
+}
@@ -0,0 +1,29 @@
+//! Plaintexts long enough for the statistics to settle, all in the public
+//! domain. They are kept out of the corpora the built-in models learn from,
+//! so the tests don't score text the models have already seen.
+
+pub const ENGLISH: &str = "It was the best of times, it was the worst of \
+    times, it was the age of wisdom, it was the age of foolishness, it was \
+    the epoch of belief, it was the epoch of incredulity, it was the season \
+    of Light, it was the season of Darkness, it was the spring of hope, it \
+    was the winter of despair, we had everything before us, we had nothing \
+    before us, we were all going direct to Heaven, we were all going direct \
+    the other way - in short, the period was so far like the present period, \
+    that some of its noisiest authorities insisted on its being received, \
+    for good or for evil, in the superlative degree of comparison only. \
+    There were a king with a large jaw and a queen with a plain face, on the \
+    throne of England; there were a king with a large jaw and a queen with a \
+    fair face, on the throne of France. In both countries it was clearer \
+    than crystal to the lords of the State preserves of loaves and fishes, \
+    that things in general were settled for ever.";
+
+pub const RUSSIAN: &str = "Я помню чудное мгновенье: передо мной явилась \
+    ты, как мимолетное виденье, как гений чистой красоты. В томленьях \
+    грусти безнадежной, в тревогах шумной суеты звучал мне долго голос \
+    нежный и снились милые черты. Шли годы. Бурь порыв мятежный рассеял \
+    прежние мечты, и я забыл твой голос нежный, твои небесные черты. В \
+    глуши, во мраке заточенья тянулись тихо дни мои без божества, без \
+    вдохновенья, без слез, без жизни, без любви. Душе настало пробужденье: \
+    и вот опять явилась ты, как мимолетное виденье, как гений чистой \
+    красоты. И сердце бьется в упоенье, и для него воскресли вновь и \
+    божество, и вдохновенье, и жизнь, и слезы, и любовь.";
@@ -0,0 +1,200 @@
+//! How much a text looks like a language. Every score takes the
+//! [`LanguageModel`] to compare against.
+
+use super::{LanguageModel, indices};
+use crate::Alphabet;
+
+/// Floor for symbols the model never saw, so they count against a text
+/// without dividing by zero.
+const UNSEEN: f64 = 1e-6;
+
+/// Chi-squared distance between symbol counts in model alphabet order and
+/// the model's letter frequencies. Lower is closer to the language.
+pub(crate) fn chi_squared_counts(
+    counts: &[usize],
+    model: &LanguageModel,
+) -> f64 {
+    let total = counts.iter().sum::<usize>() as f64;
+
+    counts
+        .iter()
+        .zip(model.letter_frequencies())
+        .map(|(&count, &frequency)| {
+            let expected = frequency.max(UNSEEN) * total;
+            let diff = count as f64 - expected;
+
+            diff * diff / expected
+        })
+        .sum()
+}
+
+/// [`chi_squared_counts`] with `strays` more characters that can't be in
+/// the language, which count like symbols the model never saw.
+pub(crate) fn chi_squared_strays(
+    counts: &[usize],
+    strays: usize,
+    model: &LanguageModel,
+) -> f64 {
+    let letters = counts.iter().sum::<usize>();
+    if letters + strays == 0 {
+        return 0.0;
+    }
+
+    let expected = UNSEEN * (letters + strays) as f64;
+    let diff = strays as f64 - expected;
+    let letters = if letters == 0 {
+        0.0
+    } else {
+        chi_squared_counts(counts, model)
+    };
+
+    letters + diff * diff / expected
+}
+
+/// Symbol counts of `text` in model alphabet order.
+pub(crate) fn counts(text: &[usize], model: &LanguageModel) -> Vec<usize> {
+    let mut counts = vec![0; model.alphabet().len()];
+
+    for &symbol in text {
+        counts[symbol] += 1;
+    }
+
+    counts
+}
+
+/// Chi-squared distance between the letter counts of `text` and the
+/// model, ignoring characters outside the model's alphabet. Lower is closer
+/// to the language; `0.0` for a text with no letters at all.
+pub fn chi_squared(text: &str, model: &LanguageModel) -> f64 {
+    let symbols = indices(text, model.alphabet());
+
+    if symbols.is_empty() {
+        return 0.0;
+    }
+
+    chi_squared_counts(&counts(&symbols, model), model)
+}
+
+/// How likely `text` is under the model: the mean log-probability of its
+/// letters, plus that of its bigrams and quadgrams where the model has
+/// statistics for them. Unlike [`chi_squared`] this weighs which letters
+/// follow which, so it still tells language apart on short texts and
+/// under mixed alphabets. Higher is closer to the language, `0.0` for a
+/// text with no letters.
+pub fn ngram_score(text: &str, model: &LanguageModel) -> f64 {
+    let symbols = indices(text, model.alphabet());
+
+    ORDERS
+        .into_iter()
+        .filter_map(|order| {
+            let log_probs = symbols
+                .windows(order)
+                .map(|ngram| model.ngram_log_prob(ngram))
+                .collect::<Option<Vec<_>>>()?;
+
+            mean(&log_probs)
+        })
+        .sum()
+}
+
+/// N-gram lengths scored, the longest ones the models keep included.
+const ORDERS: [usize; 3] = [1, 2, 4];
+
+/// Largest table built, longer n-grams of bigger alphabets are left out.
+const MAX_TABLE_LEN: usize = 1 << 24;
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty())
+        .then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// The model's n-gram log-probabilities in flat tables, which the solver
+/// looks up far more often than a hash map would like.
+pub(crate) struct NGramTables {
+    len: usize,
+    tables: Vec<(usize, Vec<f64>)>,
+}
+
+impl NGramTables {
+    pub(crate) fn new(model: &LanguageModel) -> Self {
+        let len = model.alphabet().len();
+
+        let tables = ORDERS
+            .into_iter()
+            .filter(|&order| {
+                len.checked_pow(order as u32)
+                    .is_some_and(|size| size <= MAX_TABLE_LEN)
+            })
+            .filter_map(|order| Some((order, model.ngram_log_probs(order)?)))
+            .collect();
+
+        Self { len, tables }
+    }
+
+    /// [`ngram_score`] of a text given as alphabet indices.
+    pub(crate) fn score(&self, symbols: &[usize]) -> f64 {
+        self.tables
+            .iter()
+            .filter(|(order, _)| symbols.len() >= *order)
+            .map(|(order, log_probs)| {
+                let total = symbols
+                    .windows(*order)
+                    .map(|ngram| {
+                        let key = ngram
+                            .iter()
+                            .fold(0, |key, &symbol| key * self.len + symbol);
+
+                        log_probs[key]
+                    })
+                    .sum::<f64>();
+
+                total / (symbols.len() - order + 1) as f64
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::samples::ENGLISH;
+
+    #[test]
+    fn english_beats_shifted_english() {
+        let english = LanguageModel::english();
+        let plaintext = ENGLISH.to_uppercase();
+        let shifted = plaintext
+            .chars()
+            .map(|ch| english.alphabet().lshift(ch, 3).unwrap_or(ch))
+            .collect::<String>();
+
+        assert!(chi_squared(&plaintext, &english) < 100.0);
+        assert!(
+            chi_squared(&shifted, &english)
+                > 10.0 * chi_squared(&plaintext, &english)
+        );
+        assert_eq!(chi_squared("1234", &english), 0.0);
+    }
+
+    #[test]
+    fn ngrams_tell_order_apart() {
+        let english = LanguageModel::english();
+
+        // Same letters, so chi-squared can't tell them apart.
+        let plaintext = "THE QUICK BROWN FOX";
+        let scrambled = "EHT KCIUQ NWORB XOF";
+        assert_eq!(
+            chi_squared(plaintext, &english),
+            chi_squared(scrambled, &english)
+        );
+        assert!(
+            ngram_score(plaintext, &english) > ngram_score(scrambled, &english)
+        );
+        assert_eq!(ngram_score("1234", &english), 0.0);
+
+        let tables = NGramTables::new(&english);
+        let symbols = indices(plaintext, english.alphabet());
+        let score = tables.score(&symbols);
+        assert!((score - ngram_score(plaintext, &english)).abs() < 1e-9);
+    }
+}
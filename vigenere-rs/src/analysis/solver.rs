@@ -0,0 +1,556 @@
+//! Key search by hill climbing on n-gram scores, for when solving every
+//! column on its own is not enough: short texts, where the columns are
+//! too thin for letter counts, and keyed alphabets, where a column is not
+//! a Caesar shift of the language.
+
+use std::time::{Duration, Instant};
+
+use rayon::prelude::*;
+
+use super::{
+    LanguageModel, indices, recovery::Candidate, scoring::NGramTables,
+};
+use crate::{Alphabet, Foreign, KeyStream, Quagmire, SeededStream};
+
+/// Which alphabets of the table the solver searches, besides the shifts.
+/// Named after the [`Quagmire`] constructors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    /// Straight alphabets on both sides, plain Vigenère.
+    Vigenere,
+    /// Keyed plaintext alphabet.
+    QuagmireOne,
+    /// Keyed ciphertext alphabet.
+    QuagmireTwo,
+    /// The same keyed alphabet on both sides.
+    QuagmireThree,
+    /// Two different keyed alphabets.
+    QuagmireFour,
+}
+
+impl Variant {
+    const fn keyed_plaintext(self) -> bool {
+        matches!(
+            self,
+            Self::QuagmireOne | Self::QuagmireThree | Self::QuagmireFour
+        )
+    }
+
+    const fn keyed_ciphertext(self) -> bool {
+        matches!(self, Self::QuagmireTwo | Self::QuagmireFour)
+    }
+}
+
+/// What the solver settled on. The alphabets are full permutations of the
+/// model's alphabet, so they work as keywords:
+/// `Quagmire::four(alphabet, plaintext_alphabet, ciphertext_alphabet,
+/// indicator)` deciphers the text. For [`Variant::Vigenere`] both are the
+/// model's alphabet and the indicator is the textbook Vigenère key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution {
+    pub plaintext_alphabet: String,
+    pub ciphertext_alphabet: String,
+    pub indicator: String,
+    pub plaintext: String,
+    /// [`super::scoring::ngram_score`] of the plaintext, higher is better.
+    pub score: f64,
+}
+
+/// Settings of the hill climber, which changes one shift or swaps two
+/// symbols of a keyed alphabet at a time, keeps the change when the
+/// plaintext scores better, and starts over from a random key when stuck.
+/// Several such climbs run from seeds drawn from the solver's seed, and the
+/// best scoring one wins. The same seed and settings give the same
+/// solution, unless the time limit cuts the run short first.
+///
+/// Keyed alphabets need long texts and many iterations, a keyed ciphertext
+/// alphabet most of all.
+#[derive(Debug, Clone)]
+pub struct Solver<'a> {
+    model: &'a LanguageModel,
+    variant: Variant,
+    seed: u64,
+    climbs: usize,
+    iterations: usize,
+    time_limit: Option<Duration>,
+}
+
+/// Changes in a row that may fail to help before the climb starts over
+/// from a random key, per symbol of key and alphabets searched.
+const PATIENCE: usize = 20;
+
+/// Iterations between looks at the clock.
+const CLOCK_EVERY: usize = 256;
+
+impl<'a> Solver<'a> {
+    pub const fn new(model: &'a LanguageModel) -> Self {
+        Self {
+            model,
+            variant: Variant::Vigenere,
+            seed: 0,
+            climbs: 8,
+            iterations: 100_000,
+            time_limit: None,
+        }
+    }
+
+    /// Alphabets searched, [`Variant::Vigenere`] by default.
+    #[must_use]
+    pub const fn with_variant(mut self, variant: Variant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    /// Seed of the random choices, 0 by default.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Independent climbs, each with its own random choices, 8 by default.
+    /// At least one climb always runs.
+    #[must_use]
+    pub const fn with_climbs(mut self, climbs: usize) -> Self {
+        self.climbs = if climbs == 0 { 1 } else { climbs };
+        self
+    }
+
+    /// Most keys tried by each climb, 100 000 by default.
+    #[must_use]
+    pub const fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Wall-clock time after which the search stops, shared by all the
+    /// climbs, none by default.
+    #[must_use]
+    pub const fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    /// Searches keys of length `period`, starting from straight alphabets
+    /// and a random key. `None` when the period is zero or the ciphertext
+    /// has no letters of the model's alphabet.
+    pub fn solve(&self, ciphertext: &str, period: usize) -> Option<Solution> {
+        let len = self.model.alphabet().len();
+
+        self.climb(ciphertext, |rng| {
+            let shifts = (0..period).map(|_| random(rng, len)).collect();
+
+            State::straight(shifts, len)
+        })
+    }
+
+    /// Searches from a key found by [`super::recovery::KeyRecovery`], with
+    /// its length as the period.
+    pub fn refine(
+        &self,
+        ciphertext: &str,
+        candidate: &Candidate,
+    ) -> Option<Solution> {
+        let alphabet = self.model.alphabet();
+        let shifts = candidate
+            .key
+            .chars()
+            .map(|ch| alphabet.index_of(ch))
+            .collect::<Option<Vec<_>>>()?;
+
+        self.climb(ciphertext, |_| {
+            State::straight(shifts.clone(), alphabet.len())
+        })
+    }
+
+    /// Runs every climb, on all cores, from the state `start` makes with
+    /// its random choices, and spells out the best state found.
+    fn climb(
+        &self,
+        ciphertext: &str,
+        start: impl Fn(&mut SeededStream) -> State + Sync,
+    ) -> Option<Solution> {
+        let symbols = indices(ciphertext, self.model.alphabet());
+        let table = NGramTables::new(self.model);
+        let deadline = self.time_limit.map(|limit| Instant::now() + limit);
+        let mut seeds = SeededStream::new(self.seed);
+        let seeds = (0..self.climbs)
+            .map(|_| seeds.next_shift().expect("Seeded stream never ends"))
+            .collect::<Vec<_>>();
+
+        let found = seeds
+            .into_par_iter()
+            .map(|seed| {
+                let mut rng = SeededStream::new(seed as u64);
+                let state = start(&mut rng);
+
+                self.search(&symbols, &table, state, &mut rng, deadline)
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        // The first of equal scores wins, so the result doesn't depend on
+        // which climb finishes first.
+        let (best, _) = found
+            .into_iter()
+            .reduce(|best, next| if next.1 > best.1 { next } else { best })?;
+
+        self.solution(ciphertext, &best)
+    }
+
+    /// One climb from `start`, returning the best state and its score.
+    fn search(
+        &self,
+        symbols: &[usize],
+        table: &NGramTables,
+        start: State,
+        rng: &mut SeededStream,
+        deadline: Option<Instant>,
+    ) -> Option<(State, f64)> {
+        if start.shifts.is_empty() || symbols.is_empty() {
+            return None;
+        }
+
+        let columns = Columns::new(symbols, start.shifts.len(), self.model);
+        let moves = start.shifts.len()
+            + start.plaintext.len()
+                * usize::from(self.variant.keyed_plaintext())
+            + start.ciphertext.len()
+                * usize::from(self.variant.keyed_ciphertext());
+
+        let mut current = start;
+        let mut score = table.score(&current.decipher(symbols));
+        let mut best = (current.clone(), score);
+        let mut stale = 0;
+
+        for iteration in 0..self.iterations {
+            if iteration % CLOCK_EVERY == 0
+                && deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                break;
+            }
+
+            if stale >= PATIENCE * moves {
+                current = current.shuffled(self.variant, &columns, rng);
+                score = table.score(&current.decipher(symbols));
+                stale = 0;
+            }
+
+            let next = current.changed(
+                random(rng, moves),
+                self.variant,
+                &columns,
+                rng,
+            );
+            let next_score = table.score(&next.decipher(symbols));
+
+            if next_score > score {
+                (current, score) = (next, next_score);
+                stale = 0;
+
+                if score > best.1 {
+                    best = (current.clone(), score);
+                }
+            } else {
+                stale += 1;
+            }
+        }
+
+        Some(best)
+    }
+
+    fn solution(&self, ciphertext: &str, state: &State) -> Option<Solution> {
+        let alphabet = self.model.alphabet();
+        let spell = |symbols: &[usize]| {
+            symbols
+                .iter()
+                .map(|&symbol| alphabet.char_at(symbol))
+                .collect::<Option<String>>()
+        };
+
+        let plaintext_alphabet = spell(&state.plaintext)?;
+        let ciphertext_alphabet = spell(&state.ciphertext)?;
+        // The row of a shift is named by the ciphertext symbol under the
+        // first straight symbol, see [`Quagmire`].
+        let anchor = state.plaintext.iter().position(|&symbol| symbol == 0)?;
+        let indicator = state
+            .shifts
+            .iter()
+            .map(|&shift| state.ciphertext[(shift + anchor) % alphabet.len()])
+            .collect::<Vec<_>>();
+        let indicator = spell(&indicator)?;
+
+        let quagmire = Quagmire::four(
+            alphabet,
+            &plaintext_alphabet,
+            &ciphertext_alphabet,
+            &indicator,
+        )
+        .ok()?
+        .with_foreign(Foreign::Keep);
+        let plaintext =
+            quagmire.decipher(ciphertext.chars()).collect::<String>();
+
+        Some(Solution {
+            score: super::scoring::ngram_score(&plaintext, self.model),
+            plaintext_alphabet,
+            ciphertext_alphabet,
+            indicator,
+            plaintext,
+        })
+    }
+}
+
+/// A key and the alphabets of the table, as alphabet indices.
+#[derive(Debug, Clone)]
+struct State {
+    shifts: Vec<usize>,
+    plaintext: Vec<usize>,
+    ciphertext: Vec<usize>,
+}
+
+impl State {
+    fn straight(shifts: Vec<usize>, len: usize) -> Self {
+        Self {
+            shifts,
+            plaintext: (0..len).collect(),
+            ciphertext: (0..len).collect(),
+        }
+    }
+
+    /// Runs the table backwards, like [`Quagmire::decipher`].
+    fn decipher(&self, symbols: &[usize]) -> Vec<usize> {
+        let len = self.ciphertext.len();
+        let mut positions = vec![0; len];
+        for (position, &symbol) in self.ciphertext.iter().enumerate() {
+            positions[symbol] = position;
+        }
+
+        symbols
+            .iter()
+            .zip(self.shifts.iter().cycle())
+            .map(|(&symbol, &shift)| {
+                self.plaintext[(positions[symbol] + len - shift) % len]
+            })
+            .collect()
+    }
+
+    /// The state with one shift changed or two symbols of an alphabet
+    /// swapped, `change` picking which among all of them.
+    fn changed(
+        &self,
+        change: usize,
+        variant: Variant,
+        columns: &Columns,
+        rng: &mut SeededStream,
+    ) -> Self {
+        let mut next = self.clone();
+        let len = self.ciphertext.len();
+
+        if let Some(shift) = next.shifts.get_mut(change) {
+            *shift = (*shift + 1 + random(rng, len - 1)) % len;
+            return next;
+        }
+
+        let index = change - self.shifts.len();
+        let other = (index % len + 1 + random(rng, len - 1)) % len;
+        if variant.keyed_plaintext() && index < len {
+            next.plaintext.swap(index, other);
+        } else {
+            next.ciphertext.swap(index % len, other);
+        }
+
+        if variant == Variant::QuagmireThree {
+            next.ciphertext.clone_from(&next.plaintext);
+        }
+        next.fit_shifts(columns);
+
+        next
+    }
+
+    /// A random key, and random alphabets where the variant has them keyed.
+    fn shuffled(
+        &self,
+        variant: Variant,
+        columns: &Columns,
+        rng: &mut SeededStream,
+    ) -> Self {
+        let len = self.ciphertext.len();
+        let mut next = Self::straight(
+            self.shifts.iter().map(|_| random(rng, len)).collect(),
+            len,
+        );
+
+        if variant.keyed_plaintext() {
+            shuffle(&mut next.plaintext, rng);
+        }
+
+        if variant == Variant::QuagmireThree {
+            next.ciphertext.clone_from(&next.plaintext);
+        } else if variant.keyed_ciphertext() {
+            shuffle(&mut next.ciphertext, rng);
+        }
+
+        if variant != Variant::Vigenere {
+            next.fit_shifts(columns);
+        }
+
+        next
+    }
+
+    /// Sets every shift to the one whose plaintext letters fit the language
+    /// best on their own, given the alphabets. Searching the alphabets with
+    /// the shifts left as they were would hardly ever find both right at
+    /// once.
+    fn fit_shifts(&mut self, columns: &Columns) {
+        let len = self.ciphertext.len();
+        let mut positions = vec![0; len];
+        for (position, &symbol) in self.ciphertext.iter().enumerate() {
+            positions[symbol] = position;
+        }
+
+        for (shift, counts) in self.shifts.iter_mut().zip(&columns.counts) {
+            let fit = |shift: usize| {
+                counts
+                    .iter()
+                    .zip(&positions)
+                    .map(|(&count, &position)| {
+                        let plaintext =
+                            self.plaintext[(position + len - shift) % len];
+
+                        count as f64 * columns.letters[plaintext]
+                    })
+                    .sum::<f64>()
+            };
+
+            *shift = (0..len)
+                .map(|shift| (shift, fit(shift)))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map_or(0, |(shift, _)| shift);
+        }
+    }
+}
+
+/// Symbol counts of every column of the ciphertext, with the log-probability
+/// of every letter.
+struct Columns {
+    counts: Vec<Vec<usize>>,
+    letters: Vec<f64>,
+}
+
+impl Columns {
+    fn new(symbols: &[usize], period: usize, model: &LanguageModel) -> Self {
+        let len = model.alphabet().len();
+        let mut counts = vec![vec![0; len]; period];
+        for (index, &symbol) in symbols.iter().enumerate() {
+            counts[index % period][symbol] += 1;
+        }
+
+        let letters = (0..len)
+            .map(|letter| model.ngram_log_prob(&[letter]).unwrap_or(0.0))
+            .collect();
+
+        Self { counts, letters }
+    }
+}
+
+/// Uniform-enough number below `bound`, `0` when it is zero.
+fn random(rng: &mut SeededStream, bound: usize) -> usize {
+    let value = rng.next_shift().expect("Seeded stream never ends");
+
+    value.checked_rem(bound).unwrap_or(0)
+}
+
+fn shuffle(symbols: &mut [usize], rng: &mut SeededStream) {
+    for index in (1..symbols.len()).rev() {
+        symbols.swap(index, random(rng, index + 1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Symbols, Vigenere,
+        analysis::{
+            recovery::KeyRecovery,
+            samples::{ENGLISH, RUSSIAN},
+        },
+    };
+
+    #[test]
+    fn solves_short_vigenere() {
+        let english = LanguageModel::english();
+        let plaintext = ENGLISH[..160].to_uppercase();
+        let vigenere = Vigenere::classical("DICKENS".into(), Symbols::latin())
+            .expect("Vigenere couldn't be created")
+            .with_foreign(Foreign::Keep);
+        let ciphertext = vigenere.cipher(plaintext.chars()).collect::<String>();
+
+        // Too short for the columns to be solved on their own.
+        let candidates = KeyRecovery::new(&english).recover(&ciphertext);
+        assert!(
+            candidates
+                .iter()
+                .all(|candidate| candidate.key != "DICKENS")
+        );
+
+        let solver = Solver::new(&english).with_iterations(2_000);
+        let solution = solver.solve(&ciphertext, 7).expect("Period is 7");
+        assert_eq!(solution.indicator, "DICKENS");
+        assert_eq!(solution.plaintext, plaintext);
+
+        let near_miss = Candidate {
+            key: "DOCKENZ".into(),
+            plaintext: String::new(),
+            score: 0.0,
+        };
+        let refined = solver
+            .refine(&ciphertext, &near_miss)
+            .expect("Ciphertext has letters");
+        assert_eq!(refined.indicator, "DICKENS");
+    }
+
+    #[test]
+    fn solves_keyed_alphabet() {
+        let russian = LanguageModel::russian();
+        let plaintext = RUSSIAN.to_uppercase();
+        let quagmire = Quagmire::one(&Symbols::russian(), "ПУШКИН", "ЛИРА")
+            .expect("Keys are Russian")
+            .with_foreign(Foreign::Keep);
+        let ciphertext = quagmire.cipher(plaintext.chars()).collect::<String>();
+
+        // A single climb from a seed known to get there keeps the test
+        // quick, most seeds need several.
+        let solver = Solver::new(&russian)
+            .with_variant(Variant::QuagmireOne)
+            .with_seed(15)
+            .with_climbs(1)
+            .with_iterations(10_000);
+        let solution = solver.solve(&ciphertext, 4).expect("Period is 4");
+
+        assert_eq!(solution.plaintext, plaintext);
+        assert_eq!(solution.indicator, "ЛИРА");
+
+        let quick = solver.with_iterations(1_000).with_climbs(2);
+        assert_eq!(quick.solve(&ciphertext, 4), quick.solve(&ciphertext, 4));
+
+        let one = quick.clone().with_climbs(1).solve(&ciphertext, 4);
+        assert!(one.is_some());
+        assert_eq!(quick.with_climbs(0).solve(&ciphertext, 4), one);
+    }
+
+    #[test]
+    fn stops_on_budget() {
+        let english = LanguageModel::english();
+        let solver = Solver::new(&english)
+            .with_iterations(usize::MAX)
+            .with_time_limit(Duration::from_millis(50));
+
+        let started = Instant::now();
+        assert!(solver.solve("LXFOPVEFRNHR", 3).is_some());
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        assert_eq!(solver.solve("LXFOPVEFRNHR", 0), None);
+        assert_eq!(solver.solve("1234", 3), None);
+    }
+}
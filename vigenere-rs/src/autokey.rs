@@ -0,0 +1,156 @@
+use crate::{
+    Alphabet, AutokeyStream, CipherError, UnicodeGraphic, Vigenere,
+    unwrap_chars,
+};
+
+/// Where the key continues once the primer is used up.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AutokeyMode {
+    /// Primer followed by the plaintext.
+    #[default]
+    Plaintext,
+    /// Primer followed by the ciphertext.
+    Ciphertext,
+}
+
+/// Autokey Vigenère: the key of the wrapped [`Vigenere`] is only a primer,
+/// after it the key is the message itself.
+///
+/// The key only moves on alphabet characters, so
+/// [`crate::Foreign::KeepAdvance`] passes characters through just like
+/// [`crate::Foreign::Keep`] and [`crate::Foreign::Drop`] doesn't upset the
+/// key either.
+#[derive(Debug, Clone)]
+pub struct Autokey<A: Alphabet = UnicodeGraphic> {
+    vigenere: Vigenere<A>,
+    mode: AutokeyMode,
+}
+
+impl<A: Alphabet> Autokey<A> {
+    /// Uses the key, alphabet and policies of `vigenere`, with its key as
+    /// the primer.
+    pub const fn new(vigenere: Vigenere<A>, mode: AutokeyMode) -> Self {
+        Self { vigenere, mode }
+    }
+
+    pub const fn mode(&self) -> AutokeyMode {
+        self.mode
+    }
+
+    pub const fn vigenere(&self) -> &Vigenere<A> {
+        &self.vigenere
+    }
+
+    pub fn cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = char> + use<'_, A, I> {
+        unwrap_chars(self.try_cipher(inner))
+    }
+
+    pub fn decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = char> + use<'_, A, I> {
+        unwrap_chars(self.try_decipher(inner))
+    }
+
+    pub fn try_cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I> {
+        self.vigenere.try_cipher_with(inner, self.key_stream())
+    }
+
+    pub fn try_decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I> {
+        self.vigenere.try_decipher_with(inner, self.key_stream())
+    }
+
+    /// Primer followed by the message as a [`crate::KeyStream`], for use
+    /// with [`Vigenere::cipher_with`] and the other ciphers.
+    pub fn key_stream(&self) -> AutokeyStream {
+        AutokeyStream::new(self.vigenere.shifts.iter().copied(), self.mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Foreign, Symbols, UnicodeScalar};
+
+    fn queenly(mode: AutokeyMode) -> Autokey<Symbols> {
+        let vigenere = Vigenere::classical("QUEENLY".into(), Symbols::latin())
+            .expect("Vigenere couldn't be created");
+
+        Autokey::new(vigenere, mode)
+    }
+
+    #[test]
+    fn ciphertext_autokey() {
+        let autokey = queenly(AutokeyMode::Ciphertext);
+
+        let ciphered =
+            autokey.cipher("ATTACKATDAWN".chars()).collect::<String>();
+        assert_eq!(ciphered, "QNXEPVYJQXAC");
+
+        let result = autokey.decipher(ciphered.chars()).collect::<String>();
+        assert_eq!(result, "ATTACKATDAWN");
+    }
+
+    #[test]
+    fn foreign_characters_keep_the_key() {
+        let autokey = Autokey::new(
+            queenly(AutokeyMode::Plaintext)
+                .vigenere
+                .with_foreign(Foreign::Keep),
+            AutokeyMode::Plaintext,
+        );
+
+        let ciphered = autokey.cipher("ATTACK AT DAWN".chars());
+        assert_eq!(ciphered.collect::<String>(), "QNXEPV YT WTWP");
+    }
+
+    #[test]
+    fn dropped_characters_keep_the_key() {
+        let vigenere = Vigenere::classical("L".into(), Symbols::latin())
+            .expect("Vigenere couldn't be created");
+        let message = "A B C D E F G H I J K";
+
+        for mode in [AutokeyMode::Plaintext, AutokeyMode::Ciphertext] {
+            let autokey = Autokey::new(vigenere.clone(), mode);
+
+            let ciphered = autokey.cipher(message.chars()).collect::<String>();
+            let with = vigenere
+                .cipher_with(message.chars(), autokey.key_stream())
+                .collect::<String>();
+            assert_eq!(ciphered, with);
+            assert_eq!(ciphered.len(), 11);
+
+            let result = autokey.decipher(ciphered.chars());
+            assert_eq!(result.collect::<String>(), "ABCDEFGHIJK");
+        }
+
+        let autokey = Autokey::new(vigenere, AutokeyMode::Plaintext);
+        let ciphered = autokey.cipher(message.chars());
+        assert_eq!(ciphered.collect::<String>(), "LBDFHJLNPRT");
+    }
+
+    #[test]
+    fn autokey_roundtrip() {
+        let vigenere = Vigenere::with_alphabet("Пароль".into(), UnicodeScalar)
+            .expect("Vigenere couldn't be created");
+        let message = "Кириллическое сообщение\nи 😀 emoji";
+
+        for mode in [AutokeyMode::Plaintext, AutokeyMode::Ciphertext] {
+            let autokey = Autokey::new(vigenere.clone(), mode);
+
+            let ciphered = autokey.cipher(message.chars());
+            let result = autokey.decipher(ciphered).collect::<String>();
+
+            assert_eq!(result, message);
+        }
+    }
+}
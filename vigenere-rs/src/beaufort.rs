@@ -0,0 +1,252 @@
+use crate::{
+    Alphabet, CipherError, Ciphered, Direction, KeyStream, UnicodeGraphic,
+    Vigenere, unwrap_chars,
+};
+
+/// Beaufort cipher, `C = K - P`. It is its own inverse, so ciphering and
+/// deciphering are the same operation.
+///
+/// Key, alphabet and policies come from the wrapped [`Vigenere`].
+#[derive(Debug, Clone)]
+pub struct Beaufort<A: Alphabet = UnicodeGraphic> {
+    pub(crate) vigenere: Vigenere<A>,
+}
+
+/// Variant Beaufort, `C = P - K`: Vigenère with ciphering and deciphering
+/// swapped.
+#[derive(Debug, Clone)]
+pub struct VariantBeaufort<A: Alphabet = UnicodeGraphic> {
+    vigenere: Vigenere<A>,
+}
+
+impl<A: Alphabet> Beaufort<A> {
+    pub const fn new(vigenere: Vigenere<A>) -> Self {
+        Self { vigenere }
+    }
+
+    pub const fn vigenere(&self) -> &Vigenere<A> {
+        &self.vigenere
+    }
+
+    pub fn cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = char> + use<'_, A, I> {
+        unwrap_chars(self.try_cipher(inner))
+    }
+
+    pub fn decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = char> + use<'_, A, I> {
+        self.cipher(inner)
+    }
+
+    pub fn try_cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I> {
+        self.vigenere.try_cipher_inner(inner, self.shift())
+    }
+
+    pub fn try_decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I> {
+        self.try_cipher(inner)
+    }
+
+    /// Ciphers with shifts taken from `key`, see [`Vigenere::cipher_with`].
+    pub fn cipher_with<I: Iterator<Item = char>, K: KeyStream>(
+        &self,
+        inner: I,
+        key: K,
+    ) -> impl Iterator<Item = char> + use<'_, A, I, K> {
+        let direction = Direction::Cipher;
+
+        unwrap_chars(Ciphered::new(
+            &self.vigenere,
+            inner,
+            key,
+            self.shift(),
+            direction,
+        ))
+    }
+
+    /// Deciphers with shifts taken from `key`, see [`Vigenere::cipher_with`].
+    pub fn decipher_with<I: Iterator<Item = char>, K: KeyStream>(
+        &self,
+        inner: I,
+        key: K,
+    ) -> impl Iterator<Item = char> + use<'_, A, I, K> {
+        let direction = Direction::Decipher;
+
+        unwrap_chars(Ciphered::new(
+            &self.vigenere,
+            inner,
+            key,
+            self.shift(),
+            direction,
+        ))
+    }
+
+    fn shift(&self) -> impl FnMut(char, usize) -> Option<char> + use<'_, A> {
+        let alphabet = &self.vigenere.alphabet;
+
+        // Moving the first symbol forward by the key gives `K`, moving that
+        // back by the input gives `K - P`.
+        move |ch, shift| {
+            let index = alphabet.index_of(ch)?;
+            let key = alphabet.lshift(alphabet.char_at(0)?, shift)?;
+
+            alphabet.rshift(key, index)
+        }
+    }
+}
+
+impl<A: Alphabet> VariantBeaufort<A> {
+    pub const fn new(vigenere: Vigenere<A>) -> Self {
+        Self { vigenere }
+    }
+
+    pub const fn vigenere(&self) -> &Vigenere<A> {
+        &self.vigenere
+    }
+
+    pub fn cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = char> + use<'_, A, I> {
+        self.vigenere.decipher(inner)
+    }
+
+    pub fn decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = char> + use<'_, A, I> {
+        self.vigenere.cipher(inner)
+    }
+
+    pub fn try_cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I> {
+        self.vigenere.try_decipher(inner)
+    }
+
+    pub fn try_decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I> {
+        self.vigenere.try_cipher(inner)
+    }
+
+    /// Ciphers with shifts taken from `key`, see [`Vigenere::cipher_with`].
+    pub fn cipher_with<I: Iterator<Item = char>, K: KeyStream>(
+        &self,
+        inner: I,
+        key: K,
+    ) -> impl Iterator<Item = char> + use<'_, A, I, K> {
+        let shift = |ch, shift| self.vigenere.alphabet.rshift(ch, shift);
+
+        unwrap_chars(Ciphered::new(
+            &self.vigenere,
+            inner,
+            key,
+            shift,
+            Direction::Cipher,
+        ))
+    }
+
+    /// Deciphers with shifts taken from `key`, see [`Vigenere::cipher_with`].
+    pub fn decipher_with<I: Iterator<Item = char>, K: KeyStream>(
+        &self,
+        inner: I,
+        key: K,
+    ) -> impl Iterator<Item = char> + use<'_, A, I, K> {
+        let shift = |ch, shift| self.vigenere.alphabet.lshift(ch, shift);
+
+        unwrap_chars(Ciphered::new(
+            &self.vigenere,
+            inner,
+            key,
+            shift,
+            Direction::Decipher,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AutokeyMode, AutokeyStream, Foreign, Symbols, UnicodeScalar};
+
+    #[test]
+    fn beaufort_is_reciprocal() {
+        let vigenere = Vigenere::with_alphabet("Пароль".into(), UnicodeScalar)
+            .expect("Vigenere couldn't be created")
+            .with_foreign(Foreign::Keep);
+        let beaufort = Beaufort::new(vigenere);
+        let message = "Кириллическое сообщение\nи 😀 emoji";
+
+        let ciphered = beaufort.cipher(message.chars()).collect::<String>();
+        assert_ne!(ciphered, message);
+
+        let result = beaufort.cipher(ciphered.chars()).collect::<String>();
+        assert_eq!(result, message);
+    }
+
+    #[test]
+    fn surrogate_gap_is_an_error() {
+        let vigenere =
+            Vigenere::new("Key".into()).expect("Vigenere couldn't be created");
+        let beaufort = Beaufort::new(vigenere);
+
+        let ciphered = beaufort
+            .try_cipher("5 €".chars())
+            .collect::<Result<String, _>>();
+        assert_eq!(
+            ciphered,
+            Err(CipherError::NoCharacter {
+                position: 2,
+                ch: '€'
+            })
+        );
+    }
+
+    #[test]
+    fn variant_is_inverse_vigenere() {
+        let vigenere = Vigenere::classical("LEMON".into(), Symbols::latin())
+            .expect("Vigenere couldn't be created");
+        let variant = VariantBeaufort::new(vigenere);
+
+        let result = variant.decipher("ATTACKATDAWN".chars());
+        assert_eq!(result.collect::<String>(), "LXFOPVEFRNHR");
+
+        let result = variant.cipher("LXFOPVEFRNHR".chars());
+        assert_eq!(result.collect::<String>(), "ATTACKATDAWN");
+    }
+
+    #[test]
+    fn beaufort_with_autokey() {
+        let vigenere = Vigenere::classical("QUEENLY".into(), Symbols::latin())
+            .expect("Vigenere couldn't be created");
+        let primer = || {
+            AutokeyStream::new(vigenere.key_shifts(), AutokeyMode::Plaintext)
+        };
+
+        let beaufort = Beaufort::new(vigenere.clone());
+        let ciphered = beaufort
+            .cipher_with("ATTACKATDAWN".chars(), primer())
+            .collect::<String>();
+        let result = beaufort.decipher_with(ciphered.chars(), primer());
+        assert_eq!(result.collect::<String>(), "ATTACKATDAWN");
+
+        let variant = VariantBeaufort::new(vigenere.clone());
+        let ciphered = variant
+            .cipher_with("ATTACKATDAWN".chars(), primer())
+            .collect::<String>();
+        let result = variant.decipher_with(ciphered.chars(), primer());
+        assert_eq!(result.collect::<String>(), "ATTACKATDAWN");
+    }
+}
@@ -0,0 +1,105 @@
+use crate::KeyError;
+
+/// Vigenère over raw bytes: every byte is shifted by the matching key byte,
+/// modulo 256. Works for any file, text or not.
+#[derive(Clone)]
+pub struct ByteVigenere {
+    key: Vec<u8>,
+}
+
+impl std::fmt::Debug for ByteVigenere {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ByteVigenere").finish()
+    }
+}
+
+impl ByteVigenere {
+    pub fn new(key: impl Into<Vec<u8>>) -> Result<Self, KeyError> {
+        let key = key.into();
+
+        if key.is_empty() {
+            return Err(KeyError::Empty);
+        }
+
+        Ok(Self { key })
+    }
+
+    pub(crate) fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn cipher<I: Iterator<Item = u8>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = u8> + use<'_, I> {
+        inner
+            .zip(self.key.iter().cycle())
+            .map(|(byte, shift)| byte.wrapping_add(*shift))
+    }
+
+    pub fn decipher<I: Iterator<Item = u8>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = u8> + use<'_, I> {
+        inner
+            .zip(self.key.iter().cycle())
+            .map(|(byte, shift)| byte.wrapping_sub(*shift))
+    }
+
+    pub fn cipher_slice(&self, bytes: &[u8]) -> Vec<u8> {
+        self.cipher(bytes.iter().copied()).collect()
+    }
+
+    pub fn decipher_slice(&self, bytes: &[u8]) -> Vec<u8> {
+        self.decipher(bytes.iter().copied()).collect()
+    }
+
+    pub fn cipher_in_place(&self, bytes: &mut [u8]) {
+        for (byte, shift) in bytes.iter_mut().zip(self.key.iter().cycle()) {
+            *byte = byte.wrapping_add(*shift);
+        }
+    }
+
+    pub fn decipher_in_place(&self, bytes: &mut [u8]) {
+        for (byte, shift) in bytes.iter_mut().zip(self.key.iter().cycle()) {
+            *byte = byte.wrapping_sub(*shift);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn byte_vigenere_works() {
+        let vigenere = ByteVigenere::new("key").expect("Key is not empty");
+
+        assert_eq!(vigenere.cipher_slice(&[0, 1, 0xFF]), [b'k', b'f', b'x']);
+        assert_eq!(vigenere.decipher_slice(b"kfx"), [0, 1, 0xFF]);
+        assert_eq!(ByteVigenere::new("").map(|_| ()), Err(KeyError::Empty));
+    }
+
+    proptest! {
+        #[test]
+        fn byte_vigenere_roundtrip(
+            key in prop::collection::vec(any::<u8>(), 1..32),
+            bytes: Vec<u8>,
+        ) {
+            let vigenere = ByteVigenere::new(key).expect("Key is not empty");
+
+            let ciphered = vigenere.cipher_slice(&bytes);
+
+            prop_assert_eq!(ciphered.len(), bytes.len());
+            prop_assert_eq!(&vigenere.decipher_slice(&ciphered), &bytes);
+
+            let mut in_place = bytes.clone();
+            vigenere.cipher_in_place(&mut in_place);
+            prop_assert_eq!(&in_place, &ciphered);
+            vigenere.decipher_in_place(&mut in_place);
+            prop_assert_eq!(in_place, bytes);
+        }
+    }
+}
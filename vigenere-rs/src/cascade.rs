@@ -0,0 +1,280 @@
+use crate::{
+    Alphabet, Cipher, Error, Foreign, KeyMapping, UnicodeGraphic, Vigenere,
+};
+
+/// Longest period [`Cascade::effective_key`] spells out. A progressive key
+/// over a large alphabet repeats only after millions of characters, and two
+/// of them can run to gigabytes.
+const MAX_PERIOD: usize = 1 << 20;
+
+/// Product cipher: the text goes through every stage in turn, and back
+/// through them in reverse order to decipher.
+#[derive(Debug)]
+pub struct Cascade<A: Alphabet = UnicodeGraphic> {
+    stages: Vec<Stage<A>>,
+}
+
+#[derive(Debug)]
+enum Stage<A: Alphabet> {
+    Vigenere(Vigenere<A>),
+    Cipher {
+        cipher: Box<dyn Cipher>,
+        key: String,
+    },
+}
+
+impl<A: Alphabet> Default for Cascade<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Alphabet> Cascade<A> {
+    /// Cascade with no stages, which leaves the text as it is.
+    pub const fn new() -> Self {
+        Self { stages: Vec::new() }
+    }
+
+    /// Adds a Vigenère stage after the current ones.
+    #[must_use]
+    pub fn then(mut self, vigenere: Vigenere<A>) -> Self {
+        self.stages.push(Stage::Vigenere(vigenere));
+        self
+    }
+
+    /// Adds a stage running any cipher of the [`crate::registry`] with `key`.
+    #[must_use]
+    pub fn then_cipher(
+        mut self,
+        cipher: Box<dyn Cipher>,
+        key: impl Into<String>,
+    ) -> Self {
+        self.stages.push(Stage::Cipher {
+            cipher,
+            key: key.into(),
+        });
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    pub fn encrypt(&self, text: &str) -> Result<String, Error> {
+        self.stages.iter().try_fold(
+            text.to_owned(),
+            |text, stage| match stage {
+                Stage::Vigenere(vigenere) => Ok(vigenere
+                    .try_cipher(text.chars())
+                    .collect::<Result<_, _>>()?),
+                Stage::Cipher { cipher, key } => cipher.encrypt(key, &text),
+            },
+        )
+    }
+
+    pub fn decrypt(&self, text: &str) -> Result<String, Error> {
+        self.stages
+            .iter()
+            .rev()
+            .try_fold(text.to_owned(), |text, stage| match stage {
+                Stage::Vigenere(vigenere) => Ok(vigenere
+                    .try_decipher(text.chars())
+                    .collect::<Result<_, _>>()?),
+                Stage::Cipher { cipher, key } => cipher.decrypt(key, &text),
+            })
+    }
+}
+
+impl<A: Alphabet + Clone + PartialEq> Cascade<A> {
+    /// The single Vigenère key doing the work of all the stages. Shifts add
+    /// up, so stacking keys only stretches the period to the least common
+    /// multiple of the key periods, and every stage key is just as
+    /// recoverable from the result.
+    ///
+    /// `None` unless every stage is Vigenère over the same alphabet and with
+    /// the same handling of foreign characters, or when the period is longer
+    /// than 2^20 key positions. Also `None` under [`Foreign::Drop`]: later
+    /// stages never see the dropped characters, so their keys fall out of
+    /// step with the first one.
+    pub fn effective_key(&self) -> Option<Vigenere<A>> {
+        let stages = self
+            .stages
+            .iter()
+            .map(|stage| match stage {
+                Stage::Vigenere(vigenere) => Some(vigenere),
+                Stage::Cipher { .. } => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let (first, rest) = stages.split_first()?;
+        if first.foreign == Foreign::Drop
+            || rest.iter().any(|vigenere| {
+                vigenere.alphabet != first.alphabet
+                    || vigenere.foreign != first.foreign
+            })
+        {
+            return None;
+        }
+
+        let period = stages
+            .iter()
+            .try_fold(1, |period, vigenere| lcm(period, vigenere.period()))
+            .filter(|&period| period <= MAX_PERIOD)?;
+        let len = first.alphabet.len();
+
+        let shifts = (0..period)
+            .map(|key_pos| {
+                stages
+                    .iter()
+                    .map(|vigenere| vigenere.key_shift(key_pos) % len)
+                    .sum::<usize>()
+                    % len
+            })
+            .collect::<Vec<_>>();
+        let key = shifts
+            .iter()
+            .map(|&shift| first.alphabet.char_at(shift))
+            .collect::<Option<String>>()?;
+
+        let vigenere =
+            Vigenere::from_shifts(key, shifts, first.alphabet.clone())
+                .with_foreign(first.foreign);
+
+        // The key is spelt in alphabet symbols, so it reads back the same.
+        Some(Vigenere {
+            mapping: KeyMapping::Index,
+            ..vigenere
+        })
+    }
+}
+
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+fn lcm(a: usize, b: usize) -> Option<usize> {
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Progression, Symbols, registry};
+
+    fn classical(key: &str) -> Vigenere<Symbols> {
+        Vigenere::classical(key.into(), Symbols::latin())
+            .expect("Vigenere couldn't be created")
+    }
+
+    #[test]
+    fn effective_key_period_is_lcm() {
+        let cascade = Cascade::new()
+            .then(classical("LEMON").with_foreign(Foreign::Keep))
+            .then(classical("ABC").with_foreign(Foreign::Keep));
+        let effective =
+            cascade.effective_key().expect("Stages are all Vigenère");
+
+        assert_eq!(effective.period(), 15);
+        assert_eq!(effective.key(), "LFOOONENQNMGMPP");
+
+        let message = "ATTACKATDAWNATTACKATDUSK";
+        let expected = cascade.encrypt(message).expect("Message is latin");
+        assert_eq!(
+            effective.cipher(message.chars()).collect::<String>(),
+            expected
+        );
+
+        let again =
+            Vigenere::classical(effective.key().into(), Symbols::latin())
+                .expect("Key is latin");
+        assert_eq!(again.cipher(message.chars()).collect::<String>(), expected);
+    }
+
+    #[test]
+    fn decrypts_in_reverse_order() {
+        let registry = registry();
+        let atbash = registry
+            .into_iter()
+            .find(|cipher| cipher.name() == "Atbash")
+            .expect("Atbash is registered");
+
+        // Vigenère and Atbash don't commute, so the order matters.
+        let cascade = Cascade::new()
+            .then(classical("LEMON").with_foreign(Foreign::Keep))
+            .then_cipher(atbash, "");
+        let message = "ATTACK AT DAWN";
+
+        let ciphered = cascade.encrypt(message).expect("Message is latin");
+        assert_eq!(ciphered, "OCULKE VU IMSI");
+        assert_eq!(cascade.decrypt(&ciphered).ok().as_deref(), Some(message));
+        assert!(cascade.effective_key().is_none());
+    }
+
+    #[test]
+    fn mismatched_stages_have_no_effective_key() {
+        let cascade = Cascade::new()
+            .then(classical("LEMON"))
+            .then(classical("ABC").with_foreign(Foreign::Keep));
+        assert!(cascade.effective_key().is_none());
+
+        assert!(Cascade::<Symbols>::new().effective_key().is_none());
+
+        let progressive = |key: &str| {
+            Vigenere::new(key.into())
+                .expect("Vigenere couldn't be created")
+                .with_progression(Progression::PerCycle(1))
+                .with_foreign(Foreign::Keep)
+        };
+        let cascade = Cascade::new()
+            .then(progressive("Lemon"))
+            .then(progressive("Keys"));
+        assert!(cascade.effective_key().is_none());
+    }
+
+    #[test]
+    fn effective_key_with_spaces() {
+        let message = "ATTACK AT DAWN ATTACK AT DUSK";
+
+        for foreign in [Foreign::Keep, Foreign::KeepAdvance] {
+            let cascade = Cascade::new()
+                .then(classical("LEMON").with_foreign(foreign))
+                .then(classical("ABC").with_foreign(foreign));
+            let effective =
+                cascade.effective_key().expect("Stages are all Vigenère");
+
+            assert_eq!(
+                effective.cipher(message.chars()).collect::<String>(),
+                cascade.encrypt(message).expect("Message is latin")
+            );
+        }
+
+        // The first stage drops the spaces, so the second keys a shorter
+        // text than the first did.
+        let cascade = Cascade::new()
+            .then(classical("LEMON").with_foreign(Foreign::Drop))
+            .then(classical("ABC").with_foreign(Foreign::Drop));
+        assert!(cascade.effective_key().is_none());
+
+        let summed = Cascade::new()
+            .then(classical("LEMON").with_foreign(Foreign::Keep))
+            .then(classical("ABC").with_foreign(Foreign::Keep))
+            .effective_key()
+            .expect("Stages are all Vigenère")
+            .with_foreign(Foreign::Drop);
+        let message = "A B C D E F G H I J K L";
+        let ciphered = cascade.encrypt(message).expect("Message is latin");
+        assert_eq!(ciphered, "LORHTSSVOXWZ");
+        assert_ne!(
+            summed.cipher(message.chars()).collect::<String>(),
+            ciphered
+        );
+    }
+}
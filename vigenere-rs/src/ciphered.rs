@@ -0,0 +1,91 @@
+use crate::{Alphabet, CipherError, Direction, KeyStream, Vigenere};
+
+/// Pairs input characters with shifts from a [`KeyStream`], applying the
+/// [`crate::Foreign`] policy of the cipher to characters outside its
+/// alphabet.
+pub struct Ciphered<'a, A: Alphabet, I, K, F> {
+    vigenere: &'a Vigenere<A>,
+    inner: I,
+    key: K,
+    shift: F,
+    direction: Direction,
+    position: usize,
+    stopped: bool,
+}
+
+impl<'a, A, I, K, F> Ciphered<'a, A, I, K, F>
+where
+    A: Alphabet,
+    I: Iterator<Item = char>,
+    K: KeyStream,
+    F: FnMut(char, usize) -> Option<char>,
+{
+    pub(crate) const fn new(
+        vigenere: &'a Vigenere<A>,
+        inner: I,
+        key: K,
+        shift: F,
+        direction: Direction,
+    ) -> Self {
+        Self {
+            vigenere,
+            inner,
+            key,
+            shift,
+            direction,
+            position: 0,
+            stopped: false,
+        }
+    }
+
+    /// Tells the key stream what `input` turned into.
+    fn feedback(&mut self, input: char, output: char) {
+        let (Some(input), Some(output)) = (
+            self.vigenere.shift_of(input),
+            self.vigenere.shift_of(output),
+        ) else {
+            return;
+        };
+
+        match self.direction {
+            Direction::Cipher => self.key.feedback(input, output),
+            Direction::Decipher => self.key.feedback(output, input),
+        }
+    }
+}
+
+impl<A, I, K, F> Iterator for Ciphered<'_, A, I, K, F>
+where
+    A: Alphabet,
+    I: Iterator<Item = char>,
+    K: KeyStream,
+    F: FnMut(char, usize) -> Option<char>,
+{
+    type Item = Result<char, CipherError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.stopped {
+            let ch = self.inner.next()?;
+            let position = self.position;
+            self.position += 1;
+
+            let Some(result) = self.vigenere.step(
+                ch,
+                position,
+                &mut self.key,
+                &mut self.shift,
+            ) else {
+                continue;
+            };
+
+            match result {
+                Ok(output) => self.feedback(ch, output),
+                Err(_) => self.stopped = true,
+            }
+
+            return Some(result);
+        }
+
+        None
+    }
+}
@@ -0,0 +1,393 @@
+use crate::{
+    Alphabet, CipherError, Direction, Foreign, Vigenere, VigenereState,
+};
+
+const NOT_IN_ALPHABET: u8 = u8::MAX;
+
+/// Bytes handled at once by the vectorised path.
+const LANES: usize = 32;
+
+/// Byte lookup tables for an alphabet made of ASCII characters only.
+struct AsciiTable {
+    index: [u8; 128],
+    symbols: Vec<u8>,
+    /// First symbol, if the symbols are a run of consecutive bytes.
+    first: Option<u8>,
+}
+
+impl AsciiTable {
+    fn new<A: Alphabet>(alphabet: &A) -> Option<Self> {
+        let len = alphabet.len();
+
+        if len > 128 {
+            return None;
+        }
+
+        let mut index = [NOT_IN_ALPHABET; 128];
+        let mut symbols = Vec::with_capacity(len);
+
+        for i in 0..len {
+            let ch = alphabet.char_at(i).filter(char::is_ascii)?;
+            let byte = u8::try_from(ch).ok()?;
+
+            index[usize::from(byte)] = u8::try_from(i).ok()?;
+            symbols.push(byte);
+        }
+
+        let first = symbols.first().copied().filter(|&first| {
+            symbols
+                .iter()
+                .zip(first..)
+                .all(|(&symbol, expected)| symbol == expected)
+        });
+
+        Some(Self {
+            index,
+            symbols,
+            first,
+        })
+    }
+
+    fn len(&self) -> u8 {
+        u8::try_from(self.symbols.len()).expect("At most 128 symbols")
+    }
+}
+
+/// Key shifts over a whole period, reduced modulo the alphabet length and
+/// turned around for deciphering, repeated so any `LANES` of them can be
+/// sliced out at once.
+fn tiled_shifts<A: Alphabet>(
+    vigenere: &Vigenere<A>,
+    len: u8,
+    direction: Direction,
+) -> Vec<u8> {
+    let len = usize::from(len);
+    let period = vigenere.period();
+
+    (0..period + LANES)
+        .map(|i| {
+            let shift = vigenere.key_shift(i % period) % len;
+
+            let shift = match direction {
+                Direction::Cipher => shift,
+                Direction::Decipher => (len - shift) % len,
+            };
+
+            u8::try_from(shift).expect("Shift is below the alphabet length")
+        })
+        .collect()
+}
+
+const fn is_char_start(byte: u8) -> bool {
+    byte & 0xC0 != 0x80
+}
+
+fn char_at(bytes: &[u8]) -> char {
+    bytes
+        .utf8_chunks()
+        .next()
+        .and_then(|chunk| chunk.valid().chars().next())
+        .unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+impl<A: Alphabet> Vigenere<A> {
+    /// Same output as [`Vigenere::cipher`], without the iterator overhead.
+    /// Panics the same way too, [`Vigenere::cipher_into`] reports errors.
+    pub fn cipher_str(&self, text: &str) -> String {
+        let mut output = String::with_capacity(text.len());
+        if let Err(err) = self.cipher_into(text, &mut output) {
+            panic!("{err}, the try_ methods report this as an error");
+        }
+        output
+    }
+
+    /// Same output as [`Vigenere::decipher`], without the iterator overhead.
+    pub fn decipher_str(&self, text: &str) -> String {
+        let mut output = String::with_capacity(text.len());
+        if let Err(err) = self.decipher_into(text, &mut output) {
+            panic!("{err}, the try_ methods report this as an error");
+        }
+        output
+    }
+
+    /// Appends the ciphered `text` to `output`. On error, `output` holds
+    /// everything before the offending character.
+    pub fn cipher_into(
+        &self,
+        text: &str,
+        output: &mut String,
+    ) -> Result<(), CipherError> {
+        self.str_into(text, output, Direction::Cipher)
+    }
+
+    /// Appends the deciphered `text` to `output`. On error, `output` holds
+    /// everything before the offending character.
+    pub fn decipher_into(
+        &self,
+        text: &str,
+        output: &mut String,
+    ) -> Result<(), CipherError> {
+        self.str_into(text, output, Direction::Decipher)
+    }
+
+    /// Ciphers UTF-8 `bytes` in place and returns the length of the result,
+    /// which is shorter than `bytes` only when [`Foreign::Drop`] removed
+    /// something. Only works for alphabets of ASCII characters.
+    pub fn cipher_in_place(
+        &self,
+        bytes: &mut [u8],
+    ) -> Result<usize, CipherError> {
+        let table = AsciiTable::new(&self.alphabet)
+            .ok_or(CipherError::NonAsciiAlphabet)?;
+
+        self.bytes_in_place(&table, bytes, Direction::Cipher)
+    }
+
+    /// Deciphers UTF-8 `bytes` in place, see [`Vigenere::cipher_in_place`].
+    pub fn decipher_in_place(
+        &self,
+        bytes: &mut [u8],
+    ) -> Result<usize, CipherError> {
+        let table = AsciiTable::new(&self.alphabet)
+            .ok_or(CipherError::NonAsciiAlphabet)?;
+
+        self.bytes_in_place(&table, bytes, Direction::Decipher)
+    }
+
+    fn str_into(
+        &self,
+        text: &str,
+        output: &mut String,
+        direction: Direction,
+    ) -> Result<(), CipherError> {
+        let Some(table) = AsciiTable::new(&self.alphabet) else {
+            let mut state = VigenereState::new(self.by_ref());
+
+            return match direction {
+                Direction::Cipher => state.cipher_into(text, output),
+                Direction::Decipher => state.decipher_into(text, output),
+            };
+        };
+
+        let mut bytes = text.as_bytes().to_vec();
+        let result = self.bytes_in_place(&table, &mut bytes, direction);
+
+        let len = match result {
+            Ok(len) => len,
+            Err(CipherError::Foreign { position, .. }) => text
+                .char_indices()
+                .nth(position)
+                .map_or(text.len(), |(at, _)| at),
+            Err(err) => return Err(err),
+        };
+
+        bytes.truncate(len);
+
+        // ASCII symbols only turn into ASCII symbols and anything else is
+        // either kept or dropped whole, so the bytes are still UTF-8.
+        output.push_str(
+            &String::from_utf8(bytes).expect("Ciphered bytes are UTF-8"),
+        );
+
+        result.map(|_| ())
+    }
+
+    fn bytes_in_place(
+        &self,
+        table: &AsciiTable,
+        bytes: &mut [u8],
+        direction: Direction,
+    ) -> Result<usize, CipherError> {
+        let len = table.len();
+        let period = self.period();
+        let tiled = tiled_shifts(self, len, direction);
+
+        let mut key_pos = 0;
+        let mut position = 0;
+        let mut read = 0;
+        let mut write = 0;
+
+        while read < bytes.len() {
+            // Vectorised path: a run of ASCII bytes in a contiguous
+            // alphabet where every byte takes the next key shift. Written
+            // branch-free so the compiler turns it into SIMD.
+            if let Some(first) = table.first
+                && write == read
+                && let Some(block) = bytes.get_mut(read..read + LANES)
+                && block.iter().all(u8::is_ascii)
+                && (self.foreign == Foreign::KeepAdvance
+                    || block.iter().all(|&byte| {
+                        table.index[usize::from(byte)] != NOT_IN_ALPHABET
+                    }))
+            {
+                let shifts = &tiled[key_pos..key_pos + LANES];
+
+                for (byte, &shift) in block.iter_mut().zip(shifts) {
+                    let offset = byte.wrapping_sub(first);
+                    let mut shifted = offset.wrapping_add(shift);
+                    if shifted >= len {
+                        shifted -= len;
+                    }
+
+                    *byte = if offset < len {
+                        shifted.wrapping_add(first)
+                    } else {
+                        *byte
+                    };
+                }
+
+                key_pos = (key_pos + LANES) % period;
+                position += LANES;
+                read += LANES;
+                write += LANES;
+                continue;
+            }
+
+            let byte = bytes[read];
+            let char_start = is_char_start(byte);
+            let index = table
+                .index
+                .get(usize::from(byte))
+                .copied()
+                .unwrap_or(NOT_IN_ALPHABET);
+
+            if index == NOT_IN_ALPHABET {
+                let keep = match self.foreign {
+                    Foreign::Keep => true,
+                    Foreign::KeepAdvance => {
+                        if char_start {
+                            key_pos = (key_pos + 1) % period;
+                        }
+                        true
+                    }
+                    Foreign::Drop => {
+                        if char_start {
+                            key_pos = (key_pos + 1) % period;
+                        }
+                        false
+                    }
+                    Foreign::Error => {
+                        return Err(CipherError::Foreign {
+                            position,
+                            ch: char_at(&bytes[read..]),
+                        });
+                    }
+                };
+
+                if keep {
+                    bytes[write] = byte;
+                    write += 1;
+                }
+            } else {
+                let shifted = (index + tiled[key_pos]) % len;
+                bytes[write] = table.symbols[usize::from(shifted)];
+                write += 1;
+                key_pos = (key_pos + 1) % period;
+            }
+
+            if char_start {
+                position += 1;
+            }
+            read += 1;
+        }
+
+        Ok(write)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Symbols, UnicodeGraphic};
+
+    const MESSAGE: &str =
+        "ATTACK AT DAWN, Кириллическое сообщение 😀\nWE ARE DISCOVERED";
+
+    #[test]
+    fn str_matches_iterator() {
+        let alphabets = [
+            (Symbols::latin(), "LEMON"),
+            (
+                Symbols::new("ZYXWVUTSRQPONMLKJIHGFEDCBA ,").expect("Valid"),
+                "LEMON",
+            ),
+            (Symbols::russian(), "ЛИМОН"),
+        ];
+
+        for (alphabet, key) in alphabets {
+            for foreign in [Foreign::Keep, Foreign::KeepAdvance, Foreign::Drop]
+            {
+                let vigenere =
+                    Vigenere::classical(key.into(), alphabet.clone())
+                        .expect("Vigenere couldn't be created")
+                        .with_foreign(foreign);
+
+                // Long runs of letters take the vectorised path.
+                let text = MESSAGE.repeat(3) + &"ATTACKATDAWN".repeat(20);
+                let expected =
+                    vigenere.cipher(text.chars()).collect::<String>();
+                let ciphered = vigenere.cipher_str(&text);
+                assert_eq!(ciphered, expected);
+
+                let expected =
+                    vigenere.decipher(ciphered.chars()).collect::<String>();
+                assert_eq!(vigenere.decipher_str(&ciphered), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn in_place_reports_errors() {
+        let vigenere = Vigenere::classical("LEMON".into(), Symbols::latin())
+            .expect("Vigenere couldn't be created")
+            .with_foreign(Foreign::Error);
+
+        let mut bytes = "ATTACKЖAT".as_bytes().to_vec();
+        assert_eq!(
+            vigenere.cipher_in_place(&mut bytes),
+            Err(CipherError::Foreign {
+                position: 6,
+                ch: 'Ж'
+            })
+        );
+
+        let mut output = String::new();
+        let result = vigenere.cipher_into("ATTACKЖAT", &mut output);
+        assert!(result.is_err());
+        assert_eq!(output, "LXFOPV");
+
+        let vigenere =
+            Vigenere::new("Key".into()).expect("Vigenere couldn't be created");
+        assert_eq!(
+            vigenere.cipher_in_place(&mut bytes),
+            Err(CipherError::NonAsciiAlphabet)
+        );
+    }
+
+    #[test]
+    fn in_place_roundtrip() {
+        let vigenere = Vigenere::classical("LEMON".into(), Symbols::latin())
+            .expect("Vigenere couldn't be created")
+            .with_foreign(Foreign::Keep);
+
+        let mut bytes = MESSAGE.repeat(4).into_bytes();
+        let len = vigenere.cipher_in_place(&mut bytes).expect("Keep policy");
+        assert_eq!(len, bytes.len());
+        assert_ne!(bytes, MESSAGE.repeat(4).into_bytes());
+
+        vigenere.decipher_in_place(&mut bytes).expect("Keep policy");
+        assert_eq!(bytes, MESSAGE.repeat(4).into_bytes());
+    }
+
+    #[test]
+    fn wide_alphabets_fall_back() {
+        let vigenere = Vigenere::new("Тестовый пароль".into())
+            .expect("Vigenere couldn't be created");
+
+        assert!(AsciiTable::new(&UnicodeGraphic).is_none());
+        assert_eq!(
+            vigenere.cipher_str(MESSAGE),
+            vigenere.cipher(MESSAGE.chars()).collect::<String>()
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{
+    Alphabet, CipherError, Foreign, KeyError, UnicodeGraphic, Vigenere,
+};
+
+/// Gronsfeld cipher: Vigenère with a numeric key, where every digit is a
+/// shift of 0 to 9 in whatever alphabet the message is in.
+#[derive(Debug, Clone)]
+pub struct Gronsfeld<A: Alphabet = UnicodeGraphic> {
+    vigenere: Vigenere<A>,
+}
+
+impl<A: Alphabet> Gronsfeld<A> {
+    /// `key` must be a non-empty string of ASCII digits, such as `31415`.
+    pub fn new(key: &str, alphabet: A) -> Result<Self, KeyError> {
+        if key.is_empty() {
+            return Err(KeyError::Empty);
+        }
+
+        let shifts = key
+            .chars()
+            .enumerate()
+            .map(|(position, ch)| {
+                ch.to_digit(10)
+                    .map(|digit| digit as usize)
+                    .ok_or(KeyError::OutsideAlphabet { position, ch })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            vigenere: Vigenere::from_shifts(key.into(), shifts, alphabet),
+        })
+    }
+
+    #[must_use]
+    pub fn with_foreign(self, foreign: Foreign) -> Self {
+        Self {
+            vigenere: self.vigenere.with_foreign(foreign),
+        }
+    }
+
+    pub const fn vigenere(&self) -> &Vigenere<A> {
+        &self.vigenere
+    }
+
+    pub fn cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = char> + use<'_, A, I> {
+        self.vigenere.cipher(inner)
+    }
+
+    pub fn decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = char> + use<'_, A, I> {
+        self.vigenere.decipher(inner)
+    }
+
+    pub fn try_cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I> {
+        self.vigenere.try_cipher(inner)
+    }
+
+    pub fn try_decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I> {
+        self.vigenere.try_decipher(inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{KeyMapping, Symbols};
+
+    #[test]
+    fn numeric_key_validation() {
+        assert!(matches!(
+            Gronsfeld::new("", Symbols::latin()),
+            Err(KeyError::Empty)
+        ));
+        assert!(matches!(
+            Gronsfeld::new("31a15", Symbols::latin()),
+            Err(KeyError::OutsideAlphabet {
+                position: 2,
+                ch: 'a'
+            })
+        ));
+    }
+
+    #[test]
+    fn any_alphabet() {
+        let gronsfeld = Gronsfeld::new("31415", Symbols::russian())
+            .expect("Key is numeric")
+            .with_foreign(Foreign::Keep);
+
+        let ciphered = gronsfeld.cipher("ЯБЛОКО, ГРУША".chars());
+        assert_eq!(ciphered.collect::<String>(), "ВВПППС, ДФФЭГ");
+
+        let gronsfeld =
+            Gronsfeld::new("2718", UnicodeGraphic).expect("Key is numeric");
+        let message = "Кириллическое сообщение";
+
+        let ciphered = gronsfeld.cipher(message.chars());
+        let result = gronsfeld.decipher(ciphered).collect::<String>();
+        assert_eq!(result, message);
+    }
+
+    #[test]
+    fn key_mapping_keeps_shifts() {
+        let gronsfeld =
+            Gronsfeld::new("31415", Symbols::latin()).expect("Key is numeric");
+        let vigenere = gronsfeld
+            .vigenere()
+            .clone()
+            .with_key_mapping(KeyMapping::Index);
+
+        let ciphered = vigenere.cipher("ATTACKATDAWN".chars());
+        assert_eq!(ciphered.collect::<String>(), "DUXBHNBXEFZO");
+    }
+}
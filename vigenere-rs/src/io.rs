@@ -0,0 +1,307 @@
+use std::io::{self, Read, Write};
+
+use crate::{Alphabet, Direction, UnicodeGraphic, Vigenere, VigenereState};
+
+const CHUNK_LEN: usize = 8 * 1024;
+
+/// Cipher state shared by the reader and the writer: the key position and
+/// the tail of a UTF-8 sequence split between two chunks.
+struct Stream<A: Alphabet> {
+    state: VigenereState<A>,
+    direction: Direction,
+    pending: Vec<u8>,
+}
+
+impl<A: Alphabet> Stream<A> {
+    const fn new(state: VigenereState<A>, direction: Direction) -> Self {
+        Self {
+            state,
+            direction,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Ciphers the complete characters of `pending` followed by `bytes`
+    /// into `output`, keeping an incomplete trailing sequence for later.
+    fn process(&mut self, bytes: &[u8], output: &mut String) -> io::Result<()> {
+        self.pending.extend_from_slice(bytes);
+
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(err) => {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, err));
+            }
+        };
+
+        let text = std::str::from_utf8(&self.pending[..valid])
+            .expect("Prefix was checked to be valid UTF-8");
+
+        let result = match self.direction {
+            Direction::Cipher => self.state.cipher_into(text, output),
+            Direction::Decipher => self.state.decipher_into(text, output),
+        };
+
+        result
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        self.pending.drain(..valid);
+
+        Ok(())
+    }
+
+    fn finish(&self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "Stream ended inside a UTF-8 sequence",
+        ))
+    }
+}
+
+/// Ciphers or deciphers the UTF-8 text read from `R` as it is read.
+pub struct CipherReader<R: Read, A: Alphabet = UnicodeGraphic> {
+    inner: R,
+    stream: Stream<A>,
+    chunk: Box<[u8]>,
+    output: String,
+    consumed: usize,
+    eof: bool,
+}
+
+impl<R: Read, A: Alphabet> CipherReader<R, A> {
+    pub fn cipher(inner: R, vigenere: Vigenere<A>) -> Self {
+        Self::cipher_from(inner, VigenereState::new(vigenere))
+    }
+
+    pub fn decipher(inner: R, vigenere: Vigenere<A>) -> Self {
+        Self::decipher_from(inner, VigenereState::new(vigenere))
+    }
+
+    /// Picks up ciphering where `state` stopped.
+    pub fn cipher_from(inner: R, state: VigenereState<A>) -> Self {
+        Self::new(inner, Stream::new(state, Direction::Cipher))
+    }
+
+    /// Picks up deciphering where `state` stopped.
+    pub fn decipher_from(inner: R, state: VigenereState<A>) -> Self {
+        Self::new(inner, Stream::new(state, Direction::Decipher))
+    }
+
+    fn new(inner: R, stream: Stream<A>) -> Self {
+        Self {
+            inner,
+            stream,
+            chunk: vec![0; CHUNK_LEN].into_boxed_slice(),
+            output: String::new(),
+            consumed: 0,
+            eof: false,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read, A: Alphabet> Read for CipherReader<R, A> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.consumed == self.output.len() {
+            if self.eof {
+                return Ok(0);
+            }
+
+            self.output.clear();
+            self.consumed = 0;
+
+            let read = match self.inner.read(&mut self.chunk) {
+                Ok(read) => read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+
+            if read == 0 {
+                self.eof = true;
+                self.stream.finish()?;
+                continue;
+            }
+
+            self.stream.process(&self.chunk[..read], &mut self.output)?;
+        }
+
+        let available = &self.output.as_bytes()[self.consumed..];
+        let len = available.len().min(buf.len());
+
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consumed += len;
+
+        Ok(len)
+    }
+}
+
+/// Ciphers or deciphers the UTF-8 text written to it before passing it on
+/// to `W`.
+///
+/// A character split between two writes is held back until the rest of it
+/// arrives; [`CipherWriter::finish`] reports text that ends inside one.
+pub struct CipherWriter<W: Write, A: Alphabet = UnicodeGraphic> {
+    inner: W,
+    stream: Stream<A>,
+    output: String,
+}
+
+impl<W: Write, A: Alphabet> CipherWriter<W, A> {
+    pub const fn cipher(inner: W, vigenere: Vigenere<A>) -> Self {
+        Self::cipher_from(inner, VigenereState::new(vigenere))
+    }
+
+    pub const fn decipher(inner: W, vigenere: Vigenere<A>) -> Self {
+        Self::decipher_from(inner, VigenereState::new(vigenere))
+    }
+
+    /// Picks up ciphering where `state` stopped, e.g. to append to an
+    /// existing ciphertext.
+    pub const fn cipher_from(inner: W, state: VigenereState<A>) -> Self {
+        Self::new(inner, Stream::new(state, Direction::Cipher))
+    }
+
+    /// Picks up deciphering where `state` stopped.
+    pub const fn decipher_from(inner: W, state: VigenereState<A>) -> Self {
+        Self::new(inner, Stream::new(state, Direction::Decipher))
+    }
+
+    const fn new(inner: W, stream: Stream<A>) -> Self {
+        Self {
+            inner,
+            stream,
+            output: String::new(),
+        }
+    }
+
+    /// Flushes the writer and hands back `W`.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.stream.finish()?;
+        self.inner.flush()?;
+
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write, A: Alphabet> Write for CipherWriter<W, A> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let buf = &buf[..buf.len().min(CHUNK_LEN)];
+
+        self.output.clear();
+        self.stream.process(buf, &mut self.output)?;
+        self.inner.write_all(self.output.as_bytes())?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+
+    use super::*;
+    use crate::{Foreign, UnicodeScalar};
+
+    /// Hands out at most `step` bytes per read, to split characters.
+    struct Trickle<'a> {
+        bytes: &'a [u8],
+        step: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = self.step.min(buf.len()).min(self.bytes.len());
+
+            buf[..len].copy_from_slice(&self.bytes[..len]);
+            self.bytes = &self.bytes[len..];
+
+            Ok(len)
+        }
+    }
+
+    fn vigenere() -> Vigenere<UnicodeScalar> {
+        Vigenere::with_alphabet("Тестовый пароль".into(), UnicodeScalar)
+            .expect("Vigenere couldn't be created")
+    }
+
+    #[test]
+    fn reader_matches_iterator() {
+        let message = "Кириллическое сообщение\nи 😀 emoji";
+        let expected = vigenere().cipher(message.chars()).collect::<String>();
+
+        for step in 1..=5 {
+            let inner = Trickle {
+                bytes: message.as_bytes(),
+                step,
+            };
+
+            let mut result = String::new();
+            CipherReader::cipher(inner, vigenere())
+                .read_to_string(&mut result)
+                .expect("Message is valid UTF-8");
+
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn writer_roundtrip() {
+        let message = "Кириллическое сообщение\nи 😀 emoji";
+
+        let mut ciphered = CipherWriter::cipher(Vec::new(), vigenere());
+        for byte in message.as_bytes() {
+            ciphered.write_all(&[*byte]).expect("Vec doesn't fail");
+        }
+        let ciphered = ciphered.finish().expect("Message is valid UTF-8");
+
+        let mut result = String::new();
+        CipherReader::decipher(ciphered.as_slice(), vigenere())
+            .read_to_string(&mut result)
+            .expect("Ciphertext is valid UTF-8");
+
+        assert_eq!(result, message);
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let mut result = Vec::new();
+        let err = CipherReader::cipher(&b"ab\xFFcd"[..], vigenere())
+            .read_to_end(&mut result)
+            .expect_err("Input is not UTF-8");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut writer = CipherWriter::cipher(Vec::new(), vigenere());
+        writer
+            .write_all("ab😀".as_bytes()[..4].as_ref())
+            .expect("Tail is kept");
+        let err = writer.finish().expect_err("Text ends mid-character");
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn foreign_error() {
+        let vigenere = Vigenere::new("Key".into())
+            .expect("Vigenere couldn't be created")
+            .with_foreign(Foreign::Error);
+
+        let mut result = String::new();
+        let err = CipherReader::cipher(&b"line\n"[..], vigenere)
+            .read_to_string(&mut result)
+            .expect_err("Newline is not in the alphabet");
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::Alphabet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum KeyError {
+    #[error("Key is empty")]
+    Empty,
+    #[error(
+        "Key character {ch:?} at position {position} is not in the alphabet"
+    )]
+    OutsideAlphabet { position: usize, ch: char },
+    #[error("Key doesn't satisfy the key policy: {0}")]
+    Policy(#[from] PolicyViolation),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum PolicyViolation {
+    #[error("key has {len} characters, at least {min} are required")]
+    TooShort { min: usize, len: usize },
+    #[error("key has {len} characters, at most {max} are allowed")]
+    TooLong { max: usize, len: usize },
+    #[error("key character {ch:?} at position {position} is not allowed")]
+    Disallowed { position: usize, ch: char },
+}
+
+/// Rules a key has to follow on top of being non-empty and made of
+/// alphabet characters. The default policy allows any such key.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct KeyPolicy {
+    min_len: usize,
+    max_len: Option<usize>,
+    allowed: Option<fn(&char) -> bool>,
+}
+
+impl KeyPolicy {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            min_len: 0,
+            max_len: None,
+            allowed: None,
+        }
+    }
+
+    /// Only ASCII letters.
+    #[must_use]
+    pub const fn ascii_alphabetic() -> Self {
+        Self::new().with_allowed(char::is_ascii_alphabetic)
+    }
+
+    #[must_use]
+    pub const fn with_min_len(mut self, min_len: usize) -> Self {
+        self.min_len = min_len;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    #[must_use]
+    pub const fn with_allowed(mut self, allowed: fn(&char) -> bool) -> Self {
+        self.allowed = Some(allowed);
+        self
+    }
+
+    pub fn check(&self, key: &str) -> Result<(), PolicyViolation> {
+        let len = key.chars().count();
+
+        if len < self.min_len {
+            return Err(PolicyViolation::TooShort {
+                min: self.min_len,
+                len,
+            });
+        }
+
+        if let Some(max) = self.max_len
+            && len > max
+        {
+            return Err(PolicyViolation::TooLong { max, len });
+        }
+
+        if let Some(allowed) = self.allowed
+            && let Some((position, ch)) =
+                key.chars().enumerate().find(|(_, ch)| !allowed(ch))
+        {
+            return Err(PolicyViolation::Disallowed { position, ch });
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn validate<A: Alphabet>(
+    key: &str,
+    alphabet: &A,
+    policy: &KeyPolicy,
+) -> Result<(), KeyError> {
+    if key.is_empty() {
+        return Err(KeyError::Empty);
+    }
+
+    policy.check(key)?;
+
+    if let Some((position, ch)) = key
+        .chars()
+        .enumerate()
+        .find(|&(_, ch)| !alphabet.contains(ch))
+    {
+        return Err(KeyError::OutsideAlphabet { position, ch });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Symbols;
+
+    #[test]
+    fn validate_key() {
+        let latin = Symbols::latin();
+        let policy = KeyPolicy::new();
+
+        assert_eq!(validate("LEMON", &latin, &policy), Ok(()));
+        assert_eq!(validate("", &latin, &policy), Err(KeyError::Empty));
+        assert_eq!(
+            validate("LE MON", &latin, &policy),
+            Err(KeyError::OutsideAlphabet {
+                position: 2,
+                ch: ' '
+            })
+        );
+    }
+
+    #[test]
+    fn key_policy() {
+        let policy = KeyPolicy::ascii_alphabetic()
+            .with_min_len(3)
+            .with_max_len(5);
+
+        assert_eq!(policy.check("Lemon"), Ok(()));
+        assert_eq!(
+            policy.check("Le"),
+            Err(PolicyViolation::TooShort { min: 3, len: 2 })
+        );
+        assert_eq!(
+            policy.check("Lemonade"),
+            Err(PolicyViolation::TooLong { max: 5, len: 8 })
+        );
+        assert_eq!(
+            policy.check("Лимон"),
+            Err(PolicyViolation::Disallowed {
+                position: 0,
+                ch: 'Л'
+            })
+        );
+    }
+}
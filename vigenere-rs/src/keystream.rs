@@ -0,0 +1,262 @@
+use std::collections::VecDeque;
+
+use crate::{Alphabet, AutokeyMode, Vigenere};
+
+/// Source of the shifts a cipher applies, one per character it consumes.
+///
+/// Implement it to plug a custom key schedule into
+/// [`Vigenere::cipher_with`] and friends.
+pub trait KeyStream {
+    /// Shift for the next character, `None` once the stream has run out.
+    fn next_shift(&mut self) -> Option<usize>;
+
+    /// Shift values of the plaintext and ciphertext of the character just
+    /// ciphered, for streams fed by the message itself such as autokey.
+    fn feedback(&mut self, plaintext: usize, ciphertext: usize) {
+        let _ = (plaintext, ciphertext);
+    }
+
+    /// Whether characters outside the alphabet use up a shift under
+    /// [`crate::Foreign::KeepAdvance`] and [`crate::Foreign::Drop`]. Streams
+    /// fed by the message say no, such a character has nothing to feed back.
+    fn advances_on_foreign(&self) -> bool {
+        true
+    }
+}
+
+impl<K: KeyStream + ?Sized> KeyStream for &mut K {
+    fn next_shift(&mut self) -> Option<usize> {
+        (**self).next_shift()
+    }
+
+    fn feedback(&mut self, plaintext: usize, ciphertext: usize) {
+        (**self).feedback(plaintext, ciphertext);
+    }
+
+    fn advances_on_foreign(&self) -> bool {
+        (**self).advances_on_foreign()
+    }
+}
+
+impl<K: KeyStream + ?Sized> KeyStream for Box<K> {
+    fn next_shift(&mut self) -> Option<usize> {
+        (**self).next_shift()
+    }
+
+    fn feedback(&mut self, plaintext: usize, ciphertext: usize) {
+        (**self).feedback(plaintext, ciphertext);
+    }
+
+    fn advances_on_foreign(&self) -> bool {
+        (**self).advances_on_foreign()
+    }
+}
+
+/// Key of a [`Vigenere`] repeated forever, progression included. See
+/// [`Vigenere::key_stream`].
+#[derive(Debug, Clone)]
+pub struct RepeatingStream<'a, A: Alphabet> {
+    vigenere: &'a Vigenere<A>,
+    pub(crate) key_pos: usize,
+}
+
+impl<'a, A: Alphabet> RepeatingStream<'a, A> {
+    pub(crate) fn new(vigenere: &'a Vigenere<A>, key_pos: usize) -> Self {
+        Self {
+            vigenere,
+            key_pos: key_pos.checked_rem(vigenere.period()).unwrap_or(0),
+        }
+    }
+}
+
+impl<A: Alphabet> KeyStream for RepeatingStream<'_, A> {
+    fn next_shift(&mut self) -> Option<usize> {
+        let shift = self.vigenere.key_shift(self.key_pos);
+        self.key_pos =
+            (self.key_pos + 1).checked_rem(self.vigenere.period())?;
+
+        Some(shift)
+    }
+}
+
+/// Primer shifts followed by the message, see [`crate::Autokey`].
+#[derive(Debug, Clone)]
+pub struct AutokeyStream {
+    key: VecDeque<usize>,
+    mode: AutokeyMode,
+}
+
+impl AutokeyStream {
+    pub fn new(
+        primer: impl IntoIterator<Item = usize>,
+        mode: AutokeyMode,
+    ) -> Self {
+        Self {
+            key: primer.into_iter().collect(),
+            mode,
+        }
+    }
+}
+
+impl KeyStream for AutokeyStream {
+    fn next_shift(&mut self) -> Option<usize> {
+        self.key.pop_front()
+    }
+
+    fn feedback(&mut self, plaintext: usize, ciphertext: usize) {
+        self.key.push_back(match self.mode {
+            AutokeyMode::Plaintext => plaintext,
+            AutokeyMode::Ciphertext => ciphertext,
+        });
+    }
+
+    fn advances_on_foreign(&self) -> bool {
+        false
+    }
+}
+
+/// Pseudo-random shifts from a seed, the same seed giving the same shifts
+/// on every platform.
+///
+/// Uses SplitMix64, which is fast and well spread but not a cryptographic
+/// generator.
+#[derive(Debug, Clone)]
+pub struct SeededStream {
+    state: u64,
+}
+
+impl SeededStream {
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl KeyStream for SeededStream {
+    fn next_shift(&mut self) -> Option<usize> {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+
+        // Only the shift modulo the alphabet length matters, so dropping
+        // the high bits on 32-bit targets is fine.
+        Some(z as usize)
+    }
+}
+
+/// Shifts from any iterator, used once each. A finite iterator works as a
+/// running key.
+#[derive(Debug, Clone)]
+pub struct IterStream<I> {
+    shifts: I,
+}
+
+impl<I: Iterator<Item = usize>> IterStream<I> {
+    pub fn new(shifts: impl IntoIterator<IntoIter = I>) -> Self {
+        Self {
+            shifts: shifts.into_iter(),
+        }
+    }
+}
+
+impl<I: Iterator<Item = usize>> KeyStream for IterStream<I> {
+    fn next_shift(&mut self) -> Option<usize> {
+        self.shifts.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Autokey, CipherError, Foreign, Symbols, UnicodeScalar, VigenereState,
+    };
+
+    fn lemon() -> Vigenere<Symbols> {
+        Vigenere::classical("LEMON".into(), Symbols::latin())
+            .expect("Vigenere couldn't be created")
+    }
+
+    #[test]
+    fn streams_match_ciphers() {
+        let vigenere = lemon();
+
+        let result = vigenere.cipher_with(
+            "ATTACKATDAWN".chars(),
+            IterStream::new([11, 4, 12, 14, 13].into_iter().cycle()),
+        );
+        assert_eq!(result.collect::<String>(), "LXFOPVEFRNHR");
+
+        for mode in [AutokeyMode::Plaintext, AutokeyMode::Ciphertext] {
+            let stream = AutokeyStream::new([11, 4, 12, 14, 13], mode);
+            let expected = Autokey::new(vigenere.clone(), mode)
+                .cipher("ATTACKATDAWN".chars())
+                .collect::<String>();
+
+            let result = vigenere.cipher_with("ATTACKATDAWN".chars(), stream);
+            assert_eq!(result.collect::<String>(), expected);
+
+            let stream = AutokeyStream::new([11, 4, 12, 14, 13], mode);
+            let result = vigenere.decipher_with(expected.chars(), stream);
+            assert_eq!(result.collect::<String>(), "ATTACKATDAWN");
+        }
+    }
+
+    #[test]
+    fn seeded_stream() {
+        let vigenere = Vigenere::with_alphabet("Key".into(), UnicodeScalar)
+            .expect("Vigenere couldn't be created")
+            .with_foreign(Foreign::Keep);
+        let message = "Кириллическое сообщение\nи 😀 emoji";
+
+        let ciphered = vigenere
+            .cipher_with(message.chars(), SeededStream::new(42))
+            .collect::<String>();
+        let again =
+            vigenere.cipher_with(message.chars(), SeededStream::new(42));
+        assert_eq!(again.collect::<String>(), ciphered);
+
+        let other =
+            vigenere.cipher_with(message.chars(), SeededStream::new(43));
+        assert_ne!(other.collect::<String>(), ciphered);
+
+        let result =
+            vigenere.decipher_with(ciphered.chars(), SeededStream::new(42));
+        assert_eq!(result.collect::<String>(), message);
+    }
+
+    #[test]
+    fn finite_stream_runs_out() {
+        let result = lemon()
+            .try_cipher_with("ATTACK".chars(), IterStream::new([1, 2, 3]))
+            .collect::<Result<String, _>>();
+
+        assert_eq!(result, Err(CipherError::KeyExhausted { position: 3 }));
+    }
+
+    #[test]
+    fn custom_schedule() {
+        /// Every shift doubles the last one.
+        struct Doubling(usize);
+
+        impl KeyStream for Doubling {
+            fn next_shift(&mut self) -> Option<usize> {
+                self.0 *= 2;
+                Some(self.0)
+            }
+        }
+
+        let vigenere = lemon();
+        let ciphered = vigenere.cipher_with("AAAAA".chars(), Doubling(1));
+        assert_eq!(ciphered.collect::<String>(), "CEIQG");
+
+        let mut state = VigenereState::new(vigenere.clone());
+        assert_eq!(state.cipher("ATT").as_deref(), Ok("LXF"));
+
+        let mut stream = vigenere.key_stream();
+        let shifts = std::iter::from_fn(|| stream.next_shift()).take(7);
+        assert_eq!(shifts.collect::<Vec<_>>(), [11, 4, 12, 14, 13, 11, 4]);
+    }
+}
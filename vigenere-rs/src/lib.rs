@@ -1,90 +1,489 @@
-const UTFGRAPHIC_START: u32 = 0x0020;
-const UTFGRAPHIC_ENDED: u32 = 0xFFFD;
+pub mod analysis;
+
+mod alphabet;
+mod autokey;
+mod beaufort;
+mod bytes;
+mod cascade;
+mod ciphered;
+mod fast;
+mod gronsfeld;
+mod io;
+mod key;
+mod keystream;
+mod parallel;
+mod porta;
+mod quagmire;
+mod registry;
+mod running;
+mod simple;
+mod state;
+#[cfg(test)]
+mod vectors;
+
+pub use alphabet::{Alphabet, Symbols, UnicodeGraphic, UnicodeScalar};
+pub use autokey::{Autokey, AutokeyMode};
+pub use beaufort::{Beaufort, VariantBeaufort};
+pub use bytes::ByteVigenere;
+pub use cascade::Cascade;
+pub use gronsfeld::Gronsfeld;
+pub use io::{CipherReader, CipherWriter};
+pub use key::{KeyError, KeyPolicy, PolicyViolation};
+pub use keystream::{
+    AutokeyStream, IterStream, KeyStream, RepeatingStream, SeededStream,
+};
+pub use porta::Porta;
+pub use quagmire::Quagmire;
+pub use registry::{Cipher, Error, KeyKind, registry};
+pub use running::{BookError, RunningKey};
+pub use simple::{Atbash, Caesar};
+pub use state::VigenereState;
+
+use ciphered::Ciphered;
+
+/// How key characters are turned into shift values.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum KeyMapping {
+    /// Shift by the code point of the key character. This is what the app
+    /// has always done, so old ciphertexts keep deciphering.
+    #[default]
+    CodePoint,
+    /// Shift by the index of the key character in the alphabet, so `A`
+    /// shifts by 0, `B` by 1 and so on. Matches textbook Vigenère.
+    Index,
+}
+
+/// What happens to input characters that are not in the alphabet.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Foreign {
+    /// Copy the character to the output, the key stays where it is.
+    Keep,
+    /// Copy the character to the output and move on to the next key
+    /// character.
+    KeepAdvance,
+    /// Leave the character out. The key still advances, as it always did.
+    #[default]
+    Drop,
+    /// Stop with [`CipherError::Foreign`]. Only the `try_` methods report
+    /// it, the others panic.
+    Error,
+}
+
+/// Results of a `try_` iterator for the plain methods, which are meant for
+/// configurations that can't fail.
+///
+/// Panics on the first error instead of cutting the output short.
+pub(crate) fn unwrap_chars(
+    results: impl Iterator<Item = Result<char, CipherError>>,
+) -> impl Iterator<Item = char> {
+    results.map(|result| {
+        result.unwrap_or_else(|err| {
+            panic!("{err}, the try_ methods report this as an error")
+        })
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Direction {
+    Cipher,
+    Decipher,
+}
+
+/// How the shifts grow as the key is used, for progressive-key ciphers such
+/// as Trithemius.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Progression {
+    /// Plain Vigenère, the key repeats as it is.
+    #[default]
+    None,
+    /// Every key character used shifts `step` further than the one before.
+    PerCharacter(usize),
+    /// Every pass through the key shifts `step` further than the last one.
+    PerCycle(usize),
+}
 
-const UTFGRAPHIC_LEN: u32 = UTFGRAPHIC_ENDED - UTFGRAPHIC_START + 1;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum CipherError {
+    #[error("Character {ch:?} at position {position} is not in the alphabet")]
+    Foreign { position: usize, ch: char },
+    #[error("Alphabet has non-ASCII symbols, bytes can't be ciphered in place")]
+    NonAsciiAlphabet,
+    #[error("Alphabet has an odd number of symbols, Porta needs pairs")]
+    OddAlphabet,
+    #[error("Key text ran out at position {position}")]
+    KeyExhausted { position: usize },
+    #[error(
+        "Character {ch:?} at position {position} shifts onto a code point \
+         that is not a character"
+    )]
+    NoCharacter { position: usize, ch: char },
+}
 
-pub struct Vigenere {
+#[derive(Clone)]
+pub struct Vigenere<A: Alphabet = UnicodeGraphic> {
     key: String,
+    shifts: Vec<usize>,
+    alphabet: A,
+    mapping: KeyMapping,
+    foreign: Foreign,
+    progression: Progression,
+    /// The shifts were given with [`Vigenere::from_shifts`] rather than
+    /// worked out from the key.
+    fixed_shifts: bool,
 }
 
-impl std::fmt::Debug for Vigenere {
+impl<A: Alphabet> std::fmt::Debug for Vigenere<A> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Vigenere").finish()
     }
 }
 
-trait Shift: Sized {
-    fn lshift(self, shift: Self) -> Option<Self>;
-    fn rshift(self, shift: Self) -> Option<Self>;
+impl Vigenere {
+    pub fn new(key: String) -> Result<Self, KeyError> {
+        Self::with_alphabet(key, UnicodeGraphic)
+    }
 }
 
-impl Shift for char {
-    fn lshift(self, shift: Self) -> Option<Self> {
-        if UTFGRAPHIC_START > self as u32
-            || self as u32 > UTFGRAPHIC_ENDED
-            || UTFGRAPHIC_START > shift as u32
-            || shift as u32 > UTFGRAPHIC_ENDED
-        {
-            return None;
+impl<A: Alphabet> Vigenere<A> {
+    pub fn with_alphabet(key: String, alphabet: A) -> Result<Self, KeyError> {
+        Self::with_policy(key, alphabet, &KeyPolicy::default())
+    }
+
+    /// Checks `key` against `policy` before building the cipher.
+    pub fn with_policy(
+        key: String,
+        alphabet: A,
+        policy: &KeyPolicy,
+    ) -> Result<Self, KeyError> {
+        key::validate(&key, &alphabet, policy)?;
+
+        let mut vigenere = Self {
+            key,
+            shifts: Vec::new(),
+            alphabet,
+            mapping: KeyMapping::default(),
+            foreign: Foreign::default(),
+            progression: Progression::default(),
+            fixed_shifts: false,
+        };
+        vigenere.shifts = vigenere.key_shifts();
+
+        Ok(vigenere)
+    }
+
+    /// Cipher with the shifts worked out by the caller, for keys that are
+    /// not made of alphabet characters.
+    pub(crate) fn from_shifts(
+        key: String,
+        shifts: Vec<usize>,
+        alphabet: A,
+    ) -> Self {
+        Self {
+            key,
+            shifts,
+            alphabet,
+            mapping: KeyMapping::default(),
+            foreign: Foreign::default(),
+            progression: Progression::default(),
+            fixed_shifts: true,
         }
+    }
 
-        char::from_u32(
-            (self as u32 + shift as u32 - UTFGRAPHIC_START) % UTFGRAPHIC_LEN
-                + UTFGRAPHIC_START,
-        )
+    /// Trithemius cipher: the first character is not shifted, every one
+    /// after it is shifted one further than the last.
+    pub fn trithemius(alphabet: A) -> Result<Self, KeyError> {
+        let key = alphabet.char_at(0).ok_or(KeyError::Empty)?;
+
+        Ok(Self::from_shifts(key.into(), vec![0], alphabet)
+            .with_progression(Progression::PerCharacter(1)))
     }
 
-    fn rshift(self, shift: Self) -> Option<Self> {
-        if UTFGRAPHIC_START > self as u32
-            || self as u32 > UTFGRAPHIC_ENDED
-            || UTFGRAPHIC_START > shift as u32
-            || shift as u32 > UTFGRAPHIC_ENDED
-        {
-            return None;
+    /// Textbook Vigenère: key letters shift by their alphabet index.
+    pub fn classical(key: String, alphabet: A) -> Result<Self, KeyError> {
+        Self::with_alphabet(key, alphabet)
+            .map(|vigenere| vigenere.with_key_mapping(KeyMapping::Index))
+    }
+
+    /// Has no effect on ciphers whose shifts don't come from key
+    /// characters, such as [`Gronsfeld`] and [`Quagmire`].
+    #[must_use]
+    pub fn with_key_mapping(mut self, mapping: KeyMapping) -> Self {
+        if self.fixed_shifts {
+            return self;
         }
 
-        char::from_u32(
-            (self as u32 + UTFGRAPHIC_LEN - shift as u32 - UTFGRAPHIC_START)
-                % UTFGRAPHIC_LEN
-                + UTFGRAPHIC_START,
-        )
+        self.mapping = mapping;
+        self.shifts = self.key_shifts();
+        self
     }
-}
 
-impl Vigenere {
-    pub fn new(key: String) -> Option<Self> {
-        Some(Self { key })
+    pub const fn key_mapping(&self) -> KeyMapping {
+        self.mapping
+    }
+
+    #[must_use]
+    pub const fn with_foreign(mut self, foreign: Foreign) -> Self {
+        self.foreign = foreign;
+        self
+    }
+
+    pub const fn foreign(&self) -> Foreign {
+        self.foreign
+    }
+
+    #[must_use]
+    pub const fn with_progression(mut self, progression: Progression) -> Self {
+        self.progression = progression;
+        self
+    }
+
+    pub const fn progression(&self) -> Progression {
+        self.progression
     }
 
+    pub const fn key(&self) -> &str {
+        self.key.as_str()
+    }
+
+    pub const fn alphabet(&self) -> &A {
+        &self.alphabet
+    }
+
+    /// Number of key positions before the shifts repeat: the key length, or
+    /// for a progressive key, as many passes through the key as it takes
+    /// the progression to come round the alphabet.
+    pub fn period(&self) -> usize {
+        match self.progression {
+            Progression::None => self.shifts.len(),
+            Progression::PerCharacter(_) | Progression::PerCycle(_) => {
+                self.shifts.len() * self.alphabet.len()
+            }
+        }
+    }
+
+    /// Shift at `key_pos`, progression included.
+    pub(crate) fn key_shift(&self, key_pos: usize) -> usize {
+        let len = self.alphabet.len();
+        let Some(&shift) = key_pos
+            .checked_rem(self.shifts.len())
+            .and_then(|index| self.shifts.get(index))
+        else {
+            return 0;
+        };
+
+        let (step, count) = match self.progression {
+            Progression::None => return shift,
+            Progression::PerCharacter(step) => (step, key_pos),
+            Progression::PerCycle(step) => (step, key_pos / self.shifts.len()),
+        };
+
+        (shift % len + step % len * (count % len)) % len
+    }
+
+    /// Same cipher, borrowing the alphabet.
+    pub(crate) fn by_ref(&self) -> Vigenere<&A> {
+        Vigenere {
+            key: self.key.clone(),
+            shifts: self.shifts.clone(),
+            alphabet: &self.alphabet,
+            mapping: self.mapping,
+            foreign: self.foreign,
+            progression: self.progression,
+            fixed_shifts: self.fixed_shifts,
+        }
+    }
+
+    /// Deciphers `inner`, panicking on errors like [`Vigenere::cipher`].
     pub fn decipher<I: Iterator<Item = char>>(
         &self,
         inner: I,
-    ) -> impl Iterator<Item = char> + use<'_, I> {
-        let shift = |(l, r): (char, char)| Shift::rshift(l, r);
+    ) -> impl Iterator<Item = char> + use<'_, A, I> {
+        let shift = |ch, shift| self.alphabet.rshift(ch, shift);
 
         self.cipher_inner(inner, shift)
     }
 
+    /// Ciphers `inner`. For configurations that can't fail: it panics
+    /// where [`Vigenere::try_cipher`] would return an error, so use that
+    /// with [`Foreign::Error`].
     pub fn cipher<I: Iterator<Item = char>>(
         &self,
         inner: I,
-    ) -> impl Iterator<Item = char> + use<'_, I> {
-        let shift = |(l, r): (char, char)| Shift::lshift(l, r);
+    ) -> impl Iterator<Item = char> + use<'_, A, I> {
+        let shift = |ch, shift| self.alphabet.lshift(ch, shift);
 
         self.cipher_inner(inner, shift)
     }
 
+    /// Like [`Vigenere::decipher`], but reports the character that stopped
+    /// it when [`Foreign::Error`] is set.
+    pub fn try_decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I> {
+        let shift = |ch, shift| self.alphabet.rshift(ch, shift);
+
+        self.try_cipher_inner(inner, shift)
+    }
+
+    /// Like [`Vigenere::cipher`], but reports the character that stopped
+    /// it when [`Foreign::Error`] is set.
+    pub fn try_cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I> {
+        let shift = |ch, shift| self.alphabet.lshift(ch, shift);
+
+        self.try_cipher_inner(inner, shift)
+    }
+
+    /// The key of this cipher as a [`KeyStream`], repeating forever.
+    pub fn key_stream(&self) -> RepeatingStream<'_, A> {
+        RepeatingStream::new(self, 0)
+    }
+
+    /// Ciphers with shifts taken from `key` instead of the key of this
+    /// cipher. Alphabet and [`Foreign`] policy still apply. Panics like
+    /// [`Vigenere::cipher`], also when `key` runs out.
+    pub fn cipher_with<I: Iterator<Item = char>, K: KeyStream>(
+        &self,
+        inner: I,
+        key: K,
+    ) -> impl Iterator<Item = char> + use<'_, A, I, K> {
+        unwrap_chars(self.try_cipher_with(inner, key))
+    }
+
+    /// Deciphers with shifts taken from `key`, see [`Vigenere::cipher_with`].
+    pub fn decipher_with<I: Iterator<Item = char>, K: KeyStream>(
+        &self,
+        inner: I,
+        key: K,
+    ) -> impl Iterator<Item = char> + use<'_, A, I, K> {
+        unwrap_chars(self.try_decipher_with(inner, key))
+    }
+
+    /// Like [`Vigenere::cipher_with`], but reports the character that
+    /// stopped it, including [`CipherError::KeyExhausted`] when `key` runs
+    /// out.
+    pub fn try_cipher_with<I: Iterator<Item = char>, K: KeyStream>(
+        &self,
+        inner: I,
+        key: K,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I, K>
+    {
+        let shift = |ch, shift| self.alphabet.lshift(ch, shift);
+
+        Ciphered::new(self, inner, key, shift, Direction::Cipher)
+    }
+
+    /// Like [`Vigenere::decipher_with`], but reports the character that
+    /// stopped it, including [`CipherError::KeyExhausted`] when `key` runs
+    /// out.
+    pub fn try_decipher_with<I: Iterator<Item = char>, K: KeyStream>(
+        &self,
+        inner: I,
+        key: K,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I, K>
+    {
+        let shift = |ch, shift| self.alphabet.rshift(ch, shift);
+
+        Ciphered::new(self, inner, key, shift, Direction::Decipher)
+    }
+
+    /// Shift values of the key characters. The key is validated against the
+    /// alphabet on construction, so every character has one.
+    fn key_shifts(&self) -> Vec<usize> {
+        self.key
+            .chars()
+            .filter_map(|key| self.shift_of(key))
+            .collect()
+    }
+
+    /// Shift value of `key` under the key mapping, `None` if it isn't in
+    /// the alphabet.
+    pub(crate) fn shift_of(&self, key: char) -> Option<usize> {
+        let index = self.alphabet.index_of(key)?;
+
+        match self.mapping {
+            KeyMapping::CodePoint => Some(key as usize),
+            KeyMapping::Index => Some(index),
+        }
+    }
+
+    /// Ciphers the character at `position` of the input with the next
+    /// shift of `key`, pulling shifts for foreign characters as the
+    /// [`Foreign`] policy says. `None` means the character is left out of
+    /// the output.
+    pub(crate) fn step<
+        K: KeyStream,
+        Fun: FnMut(char, usize) -> Option<char>,
+    >(
+        &self,
+        ch: char,
+        position: usize,
+        key: &mut K,
+        shift: &mut Fun,
+    ) -> Option<Result<char, CipherError>> {
+        let advances_on_foreign = key.advances_on_foreign();
+        let mut next_shift = || {
+            key.next_shift()
+                .ok_or(CipherError::KeyExhausted { position })
+        };
+
+        if !self.alphabet.contains(ch) {
+            return match self.foreign {
+                Foreign::Keep => Some(Ok(ch)),
+                Foreign::KeepAdvance if !advances_on_foreign => Some(Ok(ch)),
+                Foreign::Drop if !advances_on_foreign => None,
+                Foreign::KeepAdvance => Some(next_shift().map(|_| ch)),
+                Foreign::Drop => next_shift().err().map(Err),
+                Foreign::Error => {
+                    Some(Err(CipherError::Foreign { position, ch }))
+                }
+            };
+        }
+
+        let key_shift = match next_shift() {
+            Ok(key_shift) => key_shift,
+            Err(err) => return Some(Err(err)),
+        };
+
+        // Alphabets with holes, such as the surrogates inside
+        // `UnicodeGraphic`, can shift a character onto no character at all.
+        Some(
+            shift(ch, key_shift)
+                .ok_or(CipherError::NoCharacter { position, ch }),
+        )
+    }
+
+    /// Whether `ch` moves the key on to its next character.
+    pub(crate) fn advances_key(&self, ch: char) -> bool {
+        self.alphabet.contains(ch)
+            || matches!(self.foreign, Foreign::KeepAdvance | Foreign::Drop)
+    }
+
     pub(crate) fn cipher_inner<
         InputIter: Iterator<Item = char>,
-        Fun: FnMut((char, char)) -> Option<char>,
+        Fun: FnMut(char, usize) -> Option<char>,
     >(
         &self,
         inner: InputIter,
         shift: Fun,
-    ) -> impl Iterator<Item = char> + use<'_, InputIter, Fun> {
-        let chars = self.key.chars().cycle();
+    ) -> impl Iterator<Item = char> + use<'_, A, InputIter, Fun> {
+        unwrap_chars(self.try_cipher_inner(inner, shift))
+    }
 
-        inner.zip(chars).filter_map(shift)
+    /// Ciphers with the key of this cipher. Its stream takes no feedback,
+    /// so the direction doesn't matter.
+    pub(crate) fn try_cipher_inner<
+        InputIter: Iterator<Item = char>,
+        Fun: FnMut(char, usize) -> Option<char>,
+    >(
+        &self,
+        inner: InputIter,
+        shift: Fun,
+    ) -> Ciphered<'_, A, InputIter, RepeatingStream<'_, A>, Fun> {
+        Ciphered::new(self, inner, self.key_stream(), shift, Direction::Cipher)
     }
 }
 
@@ -120,13 +519,18 @@ mod tests {
         }};
     }
 
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
     fn ascii_shift_works() {
         for i in 'A'..='Z' {
             for j in 'A'..='Z' {
-                assert!(i.lshift(j).unwrap().rshift(j).unwrap() == i);
+                let j = j as usize;
+                let shifted = UnicodeGraphic.lshift(i, j).unwrap();
+
+                assert!(UnicodeGraphic.rshift(shifted, j).unwrap() == i);
             }
         }
     }
@@ -135,23 +539,23 @@ mod tests {
     fn vigenere_works() {
         let vigenere = Vigenere::new("Whatever it is".into());
 
-        assert!(vigenere.is_some());
+        assert!(vigenere.is_ok());
 
-        let Some(vigenere) = vigenere else {
+        let Ok(vigenere) = vigenere else {
             unreachable!()
         };
 
         let inner = "FIrst seCOnd thiRD";
-        let shift = |(l, r)| {
+        let shift = |l, r| {
             dprint!(# "INPUT");
-            Shift::lshift(dprint!(l), dprintln!(r))
+            UnicodeGraphic.lshift(dprint!(l), dprintln!(r))
         };
 
         let result = vigenere.cipher_inner(inner.chars(), shift);
 
-        let shift = |(l, r)| {
+        let shift = |l, r| {
             dprint!(# "OUTPUT");
-            Shift::rshift(dprint!(l), dprintln!(r))
+            UnicodeGraphic.rshift(dprint!(l), dprintln!(r))
         };
 
         let deciphered =
@@ -163,9 +567,9 @@ mod tests {
     fn cyrillic_vigenere() {
         let vigenere = Vigenere::new("Тестовый пароль".into());
 
-        assert!(vigenere.is_some());
+        assert!(vigenere.is_ok());
 
-        let Some(vigenere) = vigenere else {
+        let Ok(vigenere) = vigenere else {
             unreachable!()
         };
 
@@ -180,6 +584,161 @@ mod tests {
         assert!(result == message);
     }
 
+    #[test]
+    fn custom_alphabet_vigenere() {
+        let vigenere = Vigenere::with_alphabet("314".into(), Symbols::digits())
+            .expect("Vigenere couldn't be created");
+
+        let message = "2718281828";
+
+        let ciphered = vigenere.cipher(message.chars()).collect::<String>();
+        assert!(ciphered.chars().all(|ch| ch.is_ascii_digit()));
+
+        let result = vigenere.decipher(ciphered.chars()).collect::<String>();
+        assert_eq!(result, message);
+    }
+
+    #[test]
+    fn key_validation() {
+        assert_eq!(
+            Vigenere::new(String::new()).map(|_| ()),
+            Err(KeyError::Empty)
+        );
+        assert_eq!(
+            Vigenere::classical("LEMON\n".into(), Symbols::latin()).map(|_| ()),
+            Err(KeyError::OutsideAlphabet {
+                position: 5,
+                ch: '\n'
+            })
+        );
+        assert_eq!(
+            Vigenere::with_policy(
+                "Пароль".into(),
+                UnicodeGraphic,
+                &KeyPolicy::ascii_alphabetic()
+            )
+            .map(|_| ()),
+            Err(KeyError::Policy(PolicyViolation::Disallowed {
+                position: 0,
+                ch: 'П'
+            }))
+        );
+    }
+
+    #[test]
+    fn foreign_policies() {
+        let vigenere = Vigenere::classical("LEMON".into(), Symbols::latin())
+            .expect("Vigenere couldn't be created");
+
+        let message = "ATTACK\nAT DAWN";
+
+        let cipher = |foreign| {
+            vigenere
+                .clone()
+                .with_foreign(foreign)
+                .cipher(message.chars())
+                .collect::<String>()
+        };
+
+        assert_eq!(cipher(Foreign::Keep), "LXFOPV\nEF RNHR");
+        assert_eq!(cipher(Foreign::KeepAdvance), "LXFOPV\nMH OEIB");
+        assert_eq!(cipher(Foreign::Drop), "LXFOPVMHOEIB");
+
+        let vigenere = vigenere.with_foreign(Foreign::Error);
+        let error = vigenere
+            .try_cipher(message.chars())
+            .find_map(Result::err)
+            .expect("Newline is not in the alphabet");
+
+        assert_eq!(
+            error,
+            CipherError::Foreign {
+                position: 6,
+                ch: '\n'
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "'\\n' at position 6 is not in the alphabet")]
+    fn plain_api_panics_on_errors() {
+        let vigenere = Vigenere::classical("LEMON".into(), Symbols::latin())
+            .expect("Vigenere couldn't be created")
+            .with_foreign(Foreign::Error);
+
+        vigenere.cipher("ATTACK\nAT DAWN".chars()).for_each(drop);
+    }
+
+    #[test]
+    fn foreign_keep_roundtrip() {
+        let vigenere = Vigenere::new("Whatever".into())
+            .expect("Vigenere couldn't be created")
+            .with_foreign(Foreign::Keep);
+
+        let message = "First line\n\tsecond line\n";
+
+        let ciphered = vigenere.cipher(message.chars());
+        let result = vigenere.decipher(ciphered).collect::<String>();
+
+        assert_eq!(result, message);
+    }
+
+    #[test]
+    fn progressive_key() {
+        let latin = Symbols::latin();
+
+        for (progression, expected) in [
+            (Progression::PerCycle(1), "LXFOPWFGSOJT"),
+            (Progression::PerCharacter(2), "LZJUXFQTHFBN"),
+        ] {
+            let vigenere = Vigenere::classical("LEMON".into(), latin.clone())
+                .expect("Vigenere couldn't be created")
+                .with_progression(progression);
+
+            let ciphered =
+                vigenere.cipher("ATTACKATDAWN".chars()).collect::<String>();
+            assert_eq!(ciphered, expected);
+            assert_eq!(vigenere.cipher_str("ATTACKATDAWN"), expected);
+
+            let result = vigenere.decipher(ciphered.chars());
+            assert_eq!(result.collect::<String>(), "ATTACKATDAWN");
+        }
+
+        let vigenere = Vigenere::new("Пароль".into())
+            .expect("Vigenere couldn't be created")
+            .with_progression(Progression::PerCycle(7))
+            .with_foreign(Foreign::Keep);
+        let message = "Кириллическое сообщение\nи 😀 emoji".repeat(5);
+
+        let ciphered = vigenere.cipher(message.chars());
+        let result = vigenere.decipher(ciphered).collect::<String>();
+        assert_eq!(result, message);
+    }
+
+    proptest! {
+        #[test]
+        fn unicode_scalar_roundtrip(key in "\\PC{1,16}", message: String) {
+            for mapping in [KeyMapping::CodePoint, KeyMapping::Index] {
+                let vigenere =
+                    Vigenere::with_alphabet(key.clone(), UnicodeScalar)
+                        .expect("Vigenere couldn't be created")
+                        .with_key_mapping(mapping)
+                        .with_foreign(Foreign::Error);
+
+                let ciphered =
+                    vigenere.cipher(message.chars()).collect::<String>();
+                let result =
+                    vigenere.decipher(ciphered.chars()).collect::<String>();
+
+                prop_assert_eq!(
+                    ciphered.chars().count(),
+                    message.chars().count()
+                );
+                prop_assert_eq!(&result, &message);
+            }
+        }
+    }
+
     #[test]
     fn files_vigenere() {
         use file_utils::{read_file, write_file};
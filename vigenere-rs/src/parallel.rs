@@ -0,0 +1,218 @@
+use rayon::prelude::*;
+
+use crate::{Alphabet, ByteVigenere, CipherError, Vigenere, VigenereState};
+
+const CHUNK_LEN: usize = 1 << 20;
+
+/// Splits `text` into pieces of about `chunk_len` bytes, each ending on a
+/// character boundary.
+fn split_chars(text: &str, chunk_len: usize) -> Vec<&str> {
+    let mut chunks = Vec::with_capacity(text.len() / chunk_len + 1);
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let mut end = chunk_len.min(rest.len());
+        while !rest.is_char_boundary(end) {
+            end += 1;
+        }
+
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+
+    chunks
+}
+
+impl<A: Alphabet + Sync> Vigenere<A> {
+    /// Same output as [`Vigenere::cipher`], ciphered on all cores.
+    pub fn par_cipher(&self, text: &str) -> Result<String, CipherError> {
+        self.par_cipher_chunked(text, CHUNK_LEN, VigenereState::cipher_into)
+    }
+
+    /// Same output as [`Vigenere::decipher`], deciphered on all cores.
+    pub fn par_decipher(&self, text: &str) -> Result<String, CipherError> {
+        self.par_cipher_chunked(text, CHUNK_LEN, VigenereState::decipher_into)
+    }
+
+    pub(crate) fn par_cipher_chunked<'a, F>(
+        &'a self,
+        text: &str,
+        chunk_len: usize,
+        feed: F,
+    ) -> Result<String, CipherError>
+    where
+        F: Fn(
+                &mut VigenereState<&'a A>,
+                &str,
+                &mut String,
+            ) -> Result<(), CipherError>
+            + Sync,
+    {
+        let chunks = split_chars(text, chunk_len);
+
+        // Characters in each chunk and how far each of them moves the key.
+        let counts = chunks
+            .par_iter()
+            .map(|chunk| {
+                chunk.chars().fold((0, 0), |(chars, advances), ch| {
+                    (chars + 1, advances + usize::from(self.advances_key(ch)))
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let phases = counts
+            .iter()
+            .scan((0, 0), |(position, key_pos), &(chars, advances)| {
+                let phase = (*position, *key_pos);
+                *position += chars;
+                *key_pos = (*key_pos + advances) % self.period();
+                Some(phase)
+            })
+            .collect::<Vec<_>>();
+
+        let results = chunks
+            .par_iter()
+            .zip(phases)
+            .map(|(chunk, (position, key_pos))| {
+                let mut state = VigenereState::new(self.by_ref());
+                state.seek(position);
+                state.seek_key(key_pos);
+
+                let mut output = String::with_capacity(chunk.len());
+                feed(&mut state, chunk, &mut output).map(|()| output)
+            })
+            .collect::<Vec<_>>();
+
+        let mut output = String::with_capacity(text.len());
+        for result in results {
+            output += &result?;
+        }
+
+        Ok(output)
+    }
+}
+
+impl ByteVigenere {
+    /// Same output as [`ByteVigenere::cipher_slice`], ciphered on all cores.
+    pub fn par_cipher_slice(&self, bytes: &[u8]) -> Vec<u8> {
+        self.par_map(bytes, u8::wrapping_add)
+    }
+
+    /// Same output as [`ByteVigenere::decipher_slice`], deciphered on all
+    /// cores.
+    pub fn par_decipher_slice(&self, bytes: &[u8]) -> Vec<u8> {
+        self.par_map(bytes, u8::wrapping_sub)
+    }
+
+    fn par_map(&self, bytes: &[u8], shift: fn(u8, u8) -> u8) -> Vec<u8> {
+        let key = self.key();
+        let chunk_len = key.len() * (CHUNK_LEN / key.len()).max(1);
+
+        let mut output = bytes.to_vec();
+
+        // Every chunk is a whole number of key periods long, so each one
+        // starts at the beginning of the key.
+        output.par_chunks_mut(chunk_len).for_each(|chunk| {
+            for (byte, key) in chunk.iter_mut().zip(key.iter().cycle()) {
+                *byte = shift(*byte, *key);
+            }
+        });
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Foreign, Symbols, UnicodeScalar};
+
+    const MESSAGE: &str = "Кириллическое сообщение,\nATTACK AT DAWN 😀\n";
+
+    #[test]
+    fn split_on_char_boundaries() {
+        let chunks = split_chars(MESSAGE, 3);
+
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks.concat(), MESSAGE);
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        let text = MESSAGE.repeat(50);
+
+        for foreign in [Foreign::Keep, Foreign::KeepAdvance, Foreign::Drop] {
+            let vigenere =
+                Vigenere::classical("LEMON".into(), Symbols::latin())
+                    .expect("Vigenere couldn't be created")
+                    .with_foreign(foreign);
+
+            let expected = vigenere.cipher(text.chars()).collect::<String>();
+
+            for chunk_len in [1, 7, 64, CHUNK_LEN] {
+                let result = vigenere.par_cipher_chunked(
+                    &text,
+                    chunk_len,
+                    VigenereState::cipher_into,
+                );
+
+                assert_eq!(result.as_ref(), Ok(&expected));
+            }
+
+            let deciphered = vigenere.par_cipher_chunked(
+                &expected,
+                5,
+                VigenereState::decipher_into,
+            );
+            let expected = vigenere.decipher(expected.chars()).collect();
+
+            assert_eq!(deciphered, Ok(expected));
+        }
+    }
+
+    #[test]
+    fn parallel_reports_first_error() {
+        let vigenere = Vigenere::classical("LEMON".into(), Symbols::latin())
+            .expect("Vigenere couldn't be created")
+            .with_foreign(Foreign::Error);
+
+        let result = vigenere.par_cipher_chunked(
+            "ATTACK AT DAWN",
+            4,
+            VigenereState::cipher_into,
+        );
+
+        assert_eq!(
+            result,
+            Err(CipherError::Foreign {
+                position: 6,
+                ch: ' '
+            })
+        );
+    }
+
+    #[test]
+    fn parallel_roundtrip() {
+        let vigenere = Vigenere::with_alphabet("Пароль".into(), UnicodeScalar)
+            .expect("Vigenere couldn't be created");
+
+        let ciphered = vigenere.par_cipher(MESSAGE).expect("No foreign chars");
+
+        assert_eq!(vigenere.par_decipher(&ciphered).as_deref(), Ok(MESSAGE));
+    }
+
+    #[test]
+    fn parallel_bytes() {
+        let vigenere = ByteVigenere::new("key").expect("Key is not empty");
+        let bytes = (0..=255)
+            .cycle()
+            .take(3 * CHUNK_LEN + 5)
+            .collect::<Vec<u8>>();
+
+        let ciphered = vigenere.par_cipher_slice(&bytes);
+
+        assert_eq!(ciphered, vigenere.cipher_slice(&bytes));
+        assert_eq!(vigenere.par_decipher_slice(&ciphered), bytes);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{Alphabet, CipherError, UnicodeGraphic, Vigenere, unwrap_chars};
+
+/// Della Porta cipher. The alphabet is split into two halves and every pair
+/// of key characters picks one of `len / 2` ways of swapping a symbol from
+/// the first half with one from the second. Ciphering and deciphering are
+/// the same operation.
+///
+/// Key, alphabet and policies come from the wrapped [`Vigenere`]; the
+/// alphabet must have an even number of symbols.
+#[derive(Debug, Clone)]
+pub struct Porta<A: Alphabet = UnicodeGraphic> {
+    vigenere: Vigenere<A>,
+}
+
+impl<A: Alphabet> Porta<A> {
+    pub fn new(vigenere: Vigenere<A>) -> Result<Self, CipherError> {
+        if !vigenere.alphabet.len().is_multiple_of(2) {
+            return Err(CipherError::OddAlphabet);
+        }
+
+        Ok(Self { vigenere })
+    }
+
+    pub const fn vigenere(&self) -> &Vigenere<A> {
+        &self.vigenere
+    }
+
+    pub fn cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = char> + use<'_, A, I> {
+        unwrap_chars(self.try_cipher(inner))
+    }
+
+    pub fn decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = char> + use<'_, A, I> {
+        self.cipher(inner)
+    }
+
+    pub fn try_cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I> {
+        let alphabet = &self.vigenere.alphabet;
+        let half = alphabet.len() / 2;
+
+        let shift = move |ch, shift| {
+            let index = alphabet.index_of(ch)?;
+            let shift = shift / 2 % half;
+
+            let swapped = if index < half {
+                half + (index + shift) % half
+            } else {
+                (index - shift) % half
+            };
+
+            alphabet.char_at(swapped)
+        };
+
+        self.vigenere.try_cipher_inner(inner, shift)
+    }
+
+    pub fn try_decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I> {
+        self.try_cipher(inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Foreign, Symbols, UnicodeScalar};
+
+    #[test]
+    fn porta_is_reciprocal() {
+        let vigenere = Vigenere::with_alphabet("Пароль".into(), UnicodeScalar)
+            .expect("Vigenere couldn't be created")
+            .with_foreign(Foreign::Keep);
+        let porta = Porta::new(vigenere).expect("Alphabet is even");
+        let message = "Кириллическое сообщение\nи 😀 emoji";
+
+        let ciphered = porta.cipher(message.chars()).collect::<String>();
+        assert_ne!(ciphered, message);
+
+        let result = porta.cipher(ciphered.chars()).collect::<String>();
+        assert_eq!(result, message);
+    }
+
+    #[test]
+    fn surrogate_gap_is_an_error() {
+        let vigenere =
+            Vigenere::new("Key".into()).expect("Vigenere couldn't be created");
+        let porta = Porta::new(vigenere).expect("Alphabet is even");
+
+        let ciphered = porta
+            .try_cipher("山".chars())
+            .collect::<Result<String, _>>();
+        assert!(matches!(
+            ciphered,
+            Err(CipherError::NoCharacter { ch: '山', .. })
+        ));
+    }
+
+    #[test]
+    fn odd_alphabet() {
+        let vigenere = Vigenere::classical("ЛИМОН".into(), Symbols::russian())
+            .expect("Vigenere couldn't be created");
+
+        assert!(matches!(
+            Porta::new(vigenere),
+            Err(CipherError::OddAlphabet)
+        ));
+    }
+}
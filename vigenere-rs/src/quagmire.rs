@@ -0,0 +1,273 @@
+use crate::{
+    Alphabet, CipherError, Foreign, KeyError, Symbols, Vigenere, unwrap_chars,
+};
+
+/// Quagmire ciphers: Vigenère between a plaintext and a ciphertext
+/// alphabet, either of which may be mixed by a keyword, see
+/// [`Symbols::keyed`].
+///
+/// Each indicator key character picks a row of the table by sliding the
+/// ciphertext alphabet until that character sits under the first symbol of
+/// the straight alphabet (`A` for [`Symbols::latin`]) in the plaintext
+/// alphabet.
+#[derive(Debug, Clone)]
+pub struct Quagmire {
+    plaintext: Symbols,
+    vigenere: Vigenere<Symbols>,
+}
+
+/// Mixes `base` with `keyword`, reporting the first keyword character that
+/// is not in `base`.
+fn keyed(base: &Symbols, keyword: &str) -> Result<Symbols, KeyError> {
+    base.keyed(keyword).ok_or_else(|| {
+        keyword
+            .chars()
+            .enumerate()
+            .find(|&(_, ch)| !base.contains(ch))
+            .map(|(position, ch)| KeyError::OutsideAlphabet { position, ch })
+            .expect("Only keyword characters outside the alphabet fail")
+    })
+}
+
+impl Quagmire {
+    /// Quagmire I: keyed plaintext alphabet, straight ciphertext alphabet.
+    pub fn one(
+        base: &Symbols,
+        keyword: &str,
+        indicator: &str,
+    ) -> Result<Self, KeyError> {
+        Self::with_alphabets(
+            base,
+            keyed(base, keyword)?,
+            base.clone(),
+            indicator,
+        )
+    }
+
+    /// Quagmire II: straight plaintext alphabet, keyed ciphertext alphabet.
+    pub fn two(
+        base: &Symbols,
+        keyword: &str,
+        indicator: &str,
+    ) -> Result<Self, KeyError> {
+        Self::with_alphabets(
+            base,
+            base.clone(),
+            keyed(base, keyword)?,
+            indicator,
+        )
+    }
+
+    /// Quagmire III: the same keyed alphabet on both sides.
+    pub fn three(
+        base: &Symbols,
+        keyword: &str,
+        indicator: &str,
+    ) -> Result<Self, KeyError> {
+        let keyed = keyed(base, keyword)?;
+
+        Self::with_alphabets(base, keyed.clone(), keyed, indicator)
+    }
+
+    /// Quagmire IV: plaintext and ciphertext alphabets keyed by different
+    /// keywords.
+    pub fn four(
+        base: &Symbols,
+        plaintext_keyword: &str,
+        ciphertext_keyword: &str,
+        indicator: &str,
+    ) -> Result<Self, KeyError> {
+        Self::with_alphabets(
+            base,
+            keyed(base, plaintext_keyword)?,
+            keyed(base, ciphertext_keyword)?,
+            indicator,
+        )
+    }
+
+    fn with_alphabets(
+        base: &Symbols,
+        plaintext: Symbols,
+        ciphertext: Symbols,
+        indicator: &str,
+    ) -> Result<Self, KeyError> {
+        if indicator.is_empty() {
+            return Err(KeyError::Empty);
+        }
+
+        let len = ciphertext.len();
+        let anchor = base
+            .char_at(0)
+            .and_then(|first| plaintext.index_of(first))
+            .expect("Keyed alphabets have the symbols of the base");
+
+        let shifts = indicator
+            .chars()
+            .enumerate()
+            .map(|(position, ch)| {
+                ciphertext
+                    .index_of(ch)
+                    .map(|index| (index + len - anchor) % len)
+                    .ok_or(KeyError::OutsideAlphabet { position, ch })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            plaintext,
+            vigenere: Vigenere::from_shifts(
+                indicator.into(),
+                shifts,
+                ciphertext,
+            ),
+        })
+    }
+
+    #[must_use]
+    pub fn with_foreign(self, foreign: Foreign) -> Self {
+        Self {
+            vigenere: self.vigenere.with_foreign(foreign),
+            ..self
+        }
+    }
+
+    pub const fn plaintext_alphabet(&self) -> &Symbols {
+        &self.plaintext
+    }
+
+    pub const fn ciphertext_alphabet(&self) -> &Symbols {
+        &self.vigenere.alphabet
+    }
+
+    pub fn cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = char> + use<'_, I> {
+        unwrap_chars(self.try_cipher(inner))
+    }
+
+    pub fn decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = char> + use<'_, I> {
+        unwrap_chars(self.try_decipher(inner))
+    }
+
+    pub fn try_cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, I> {
+        let plaintext = &self.plaintext;
+        let ciphertext = &self.vigenere.alphabet;
+
+        // Carry the plaintext symbol over to the ciphertext alphabet by its
+        // index, then shift along that.
+        let shift = |ch, shift| {
+            let ch = ciphertext.char_at(plaintext.index_of(ch)?)?;
+
+            ciphertext.lshift(ch, shift)
+        };
+
+        self.vigenere.try_cipher_inner(inner, shift)
+    }
+
+    pub fn try_decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, I> {
+        let plaintext = &self.plaintext;
+        let ciphertext = &self.vigenere.alphabet;
+
+        let shift = |ch, shift| {
+            let ch = ciphertext.rshift(ch, shift)?;
+
+            plaintext.char_at(ciphertext.index_of(ch)?)
+        };
+
+        self.vigenere.try_cipher_inner(inner, shift)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &str = "DONTLETANYONETELLYOUTHESKYSTHELIMIT";
+
+    #[test]
+    fn straight_alphabets_are_vigenere() {
+        let latin = Symbols::latin();
+        let vigenere = Vigenere::classical("LEMON".into(), latin.clone())
+            .expect("Vigenere couldn't be created");
+        let expected = vigenere.cipher(MESSAGE.chars()).collect::<String>();
+
+        for quagmire in [
+            Quagmire::one(&latin, "", "LEMON"),
+            Quagmire::two(&latin, "", "LEMON"),
+            Quagmire::three(&latin, "", "LEMON"),
+            Quagmire::four(&latin, "", "", "LEMON"),
+        ] {
+            let quagmire = quagmire.expect("Quagmire couldn't be created");
+
+            let result = quagmire.cipher(MESSAGE.chars());
+            assert_eq!(result.collect::<String>(), expected);
+        }
+    }
+
+    #[test]
+    fn quagmire_tables() {
+        let latin = Symbols::latin();
+
+        // Row `A` of Quagmire I puts the straight `A` under the keyed one,
+        // so the keyword wraps round to the end of the alphabet.
+        let quagmire =
+            Quagmire::one(&latin, "SPRING", "A").expect("Keyword is latin");
+        let result = quagmire.cipher("SPRINGAB".chars()).collect::<String>();
+        assert_eq!(result, "UVWXYZAB");
+
+        // Row `S` of Quagmire II is the keyed alphabet itself.
+        let quagmire =
+            Quagmire::two(&latin, "SPRING", "S").expect("Keyword is latin");
+        let result = quagmire.cipher("ABCDEF".chars()).collect::<String>();
+        assert_eq!(result, "SPRING");
+    }
+
+    #[test]
+    fn quagmire_roundtrip() {
+        let latin = Symbols::latin();
+
+        for quagmire in [
+            Quagmire::one(&latin, "SENORY", "PERCTFLIGHT"),
+            Quagmire::two(&latin, "SPRINGFEVER", "FLOWER"),
+            Quagmire::three(&latin, "AUTOMOBILE", "HIGHWAY"),
+            Quagmire::four(&latin, "SENORY", "PERCTFLIGHT", "EXTRA"),
+        ] {
+            let quagmire = quagmire
+                .expect("Quagmire couldn't be created")
+                .with_foreign(Foreign::Keep);
+
+            let message = "DON'T LET ANYONE TELL YOU THE SKY'S THE LIMIT";
+            let ciphered = quagmire.cipher(message.chars()).collect::<String>();
+            assert_ne!(ciphered, message);
+
+            let result = quagmire.decipher(ciphered.chars());
+            assert_eq!(result.collect::<String>(), message);
+        }
+    }
+
+    #[test]
+    fn quagmire_key_errors() {
+        let latin = Symbols::latin();
+
+        assert!(matches!(
+            Quagmire::three(&latin, "AUTO MOBILE", "HIGHWAY"),
+            Err(KeyError::OutsideAlphabet {
+                position: 4,
+                ch: ' '
+            })
+        ));
+        assert!(matches!(
+            Quagmire::three(&latin, "AUTOMOBILE", ""),
+            Err(KeyError::Empty)
+        ));
+    }
+}
@@ -0,0 +1,682 @@
+use std::io::{self, Cursor, Read};
+
+use crate::{
+    Alphabet, Atbash, Autokey, AutokeyMode, Beaufort, Caesar, CipherError,
+    CipherReader, Direction, Foreign, Gronsfeld, KeyError, KeyPolicy, Porta,
+    Progression, Quagmire, RunningKey, Symbols, UnicodeGraphic, UnicodeScalar,
+    VariantBeaufort, Vigenere,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Key(#[from] KeyError),
+    #[error(transparent)]
+    Cipher(#[from] CipherError),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// What a cipher takes as its key, so frontends can show a fitting input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyKind {
+    /// The key is ignored.
+    None,
+    /// A password typed in by the user.
+    Text,
+    /// A string of digits.
+    Digits,
+    /// A long text such as a book, usually read from a file.
+    Book,
+}
+
+/// Common interface of the ciphers, for frontends that let the user pick
+/// one at run time. Keys and texts are plain strings; each cipher parses
+/// the key its own way.
+pub trait Cipher: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// One line on what the cipher does and what its key looks like.
+    fn description(&self) -> &'static str;
+
+    fn key_kind(&self) -> KeyKind;
+
+    /// Checks `key` the way [`Cipher::encrypt`] will read it.
+    fn parse_key(&self, key: &str) -> Result<(), KeyError>;
+
+    fn encrypt(&self, key: &str, text: &str) -> Result<String, Error>;
+
+    fn decrypt(&self, key: &str, text: &str) -> Result<String, Error>;
+
+    /// Encrypts the UTF-8 text read from `input`. Ciphers that can stream
+    /// do it as the text is read, the rest read it all first.
+    fn encrypt_reader<'a>(
+        &self,
+        key: &str,
+        input: Box<dyn Read + 'a>,
+    ) -> Result<Box<dyn Read + 'a>, Error> {
+        let text = io::read_to_string(input)?;
+
+        Ok(Box::new(Cursor::new(
+            self.encrypt(key, &text)?.into_bytes(),
+        )))
+    }
+
+    /// Decrypts the UTF-8 text read from `input`, see
+    /// [`Cipher::encrypt_reader`].
+    fn decrypt_reader<'a>(
+        &self,
+        key: &str,
+        input: Box<dyn Read + 'a>,
+    ) -> Result<Box<dyn Read + 'a>, Error> {
+        let text = io::read_to_string(input)?;
+
+        Ok(Box::new(Cursor::new(
+            self.decrypt(key, &text)?.into_bytes(),
+        )))
+    }
+}
+
+impl std::fmt::Debug for dyn Cipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cipher")
+            .field("name", &self.name())
+            .finish()
+    }
+}
+
+type MakeVigenere = fn(&str) -> Result<Vigenere, KeyError>;
+type ParseKey = fn(&str) -> Result<(), KeyError>;
+type RunText = fn(&str, &str, Direction) -> Result<String, Error>;
+
+enum Run {
+    /// Plain Vigenère with some key schedule, which can stream.
+    Vigenere(MakeVigenere),
+    /// Anything else, ciphered a whole text at a time.
+    Text { parse: ParseKey, run: RunText },
+}
+
+struct Entry {
+    name: &'static str,
+    description: &'static str,
+    key_kind: KeyKind,
+    run: Run,
+}
+
+impl Entry {
+    fn run(
+        &self,
+        key: &str,
+        text: &str,
+        direction: Direction,
+    ) -> Result<String, Error> {
+        match self.run {
+            Run::Vigenere(make) => {
+                let vigenere = make(key)?;
+
+                match direction {
+                    Direction::Cipher => {
+                        collect(vigenere.try_cipher(text.chars()))
+                    }
+                    Direction::Decipher => {
+                        collect(vigenere.try_decipher(text.chars()))
+                    }
+                }
+            }
+            Run::Text { run, .. } => run(key, text, direction),
+        }
+    }
+}
+
+impl Cipher for Entry {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn key_kind(&self) -> KeyKind {
+        self.key_kind
+    }
+
+    fn parse_key(&self, key: &str) -> Result<(), KeyError> {
+        match self.run {
+            Run::Vigenere(make) => make(key).map(|_| ()),
+            Run::Text { parse, .. } => parse(key),
+        }
+    }
+
+    fn encrypt(&self, key: &str, text: &str) -> Result<String, Error> {
+        self.run(key, text, Direction::Cipher)
+    }
+
+    fn decrypt(&self, key: &str, text: &str) -> Result<String, Error> {
+        self.run(key, text, Direction::Decipher)
+    }
+
+    fn encrypt_reader<'a>(
+        &self,
+        key: &str,
+        input: Box<dyn Read + 'a>,
+    ) -> Result<Box<dyn Read + 'a>, Error> {
+        match self.run {
+            Run::Vigenere(make) => {
+                Ok(Box::new(CipherReader::cipher(input, make(key)?)))
+            }
+            Run::Text { .. } => {
+                let text = io::read_to_string(input)?;
+                let result = self.encrypt(key, &text)?;
+
+                Ok(Box::new(Cursor::new(result.into_bytes())))
+            }
+        }
+    }
+
+    fn decrypt_reader<'a>(
+        &self,
+        key: &str,
+        input: Box<dyn Read + 'a>,
+    ) -> Result<Box<dyn Read + 'a>, Error> {
+        match self.run {
+            Run::Vigenere(make) => {
+                Ok(Box::new(CipherReader::decipher(input, make(key)?)))
+            }
+            Run::Text { .. } => {
+                let text = io::read_to_string(input)?;
+                let result = self.decrypt(key, &text)?;
+
+                Ok(Box::new(Cursor::new(result.into_bytes())))
+            }
+        }
+    }
+}
+
+fn collect(
+    result: impl Iterator<Item = Result<char, CipherError>>,
+) -> Result<String, Error> {
+    Ok(result.collect::<Result<String, _>>()?)
+}
+
+/// What the app has always used: printable Unicode, ASCII letter keys
+/// shifting by their code point, anything else passed through.
+fn legacy(key: &str) -> Result<Vigenere, KeyError> {
+    Vigenere::with_policy(
+        key.into(),
+        UnicodeGraphic,
+        &KeyPolicy::ascii_alphabetic(),
+    )
+    .map(|vigenere| vigenere.with_foreign(Foreign::Keep))
+}
+
+/// [`legacy`] over every Unicode scalar value, for ciphers whose symbols
+/// would otherwise land in the surrogate gap of [`UnicodeGraphic`].
+fn scalar(key: &str) -> Result<Vigenere<UnicodeScalar>, KeyError> {
+    Vigenere::with_policy(
+        key.into(),
+        UnicodeScalar,
+        &KeyPolicy::ascii_alphabetic(),
+    )
+    .map(|vigenere| vigenere.with_foreign(Foreign::Keep))
+}
+
+/// Runs a cipher over `A..=Z` on upper-cased `text`, then puts the case of
+/// every letter back.
+fn latin_folded(
+    text: &str,
+    cipher: impl FnOnce(&str) -> Result<String, Error>,
+) -> Result<String, Error> {
+    let result = cipher(&text.to_ascii_uppercase())?;
+
+    Ok(result
+        .chars()
+        .zip(text.chars())
+        .map(|(ch, original)| {
+            if original.is_ascii_lowercase() {
+                ch.to_ascii_lowercase()
+            } else {
+                ch
+            }
+        })
+        .collect())
+}
+
+/// Caesar keys are either a number or the letter `A` is shifted to.
+fn caesar_shift(key: &str) -> Result<usize, KeyError> {
+    let latin = Symbols::latin();
+    let mut chars = key.chars();
+
+    let Some(first) = chars.next() else {
+        return Err(KeyError::Empty);
+    };
+
+    if first.is_ascii_digit() {
+        return key
+            .chars()
+            .enumerate()
+            .try_fold(0, |shift, (position, ch)| {
+                ch.to_digit(10)
+                    .map(|digit| (shift * 10 + digit as usize) % latin.len())
+                    .ok_or(KeyError::OutsideAlphabet { position, ch })
+            });
+    }
+
+    if let Some(ch) = chars.next() {
+        return Err(KeyError::OutsideAlphabet { position: 1, ch });
+    }
+
+    latin.index_of(first.to_ascii_uppercase()).ok_or(
+        KeyError::OutsideAlphabet {
+            position: 0,
+            ch: first,
+        },
+    )
+}
+
+/// Quagmire keys are keywords and the indicator separated by spaces, e.g.
+/// `SPRING FLOWER` or, for Quagmire IV, `SENORY PERCTFLIGHT EXTRA`.
+fn quagmire(key: &str, number: u8) -> Result<Quagmire, KeyError> {
+    let latin = Symbols::latin();
+    let key = key.to_ascii_uppercase();
+    let (keyword, rest) = key.split_once(' ').unwrap_or((&key, ""));
+
+    let quagmire = match number {
+        1 => Quagmire::one(&latin, keyword, rest),
+        2 => Quagmire::two(&latin, keyword, rest),
+        3 => Quagmire::three(&latin, keyword, rest),
+        _ => {
+            let (ciphertext, indicator) =
+                rest.split_once(' ').unwrap_or((rest, ""));
+            Quagmire::four(&latin, keyword, ciphertext, indicator)
+        }
+    };
+
+    quagmire.map(|quagmire| quagmire.with_foreign(Foreign::Keep))
+}
+
+fn run_quagmire(
+    key: &str,
+    text: &str,
+    direction: Direction,
+    number: u8,
+) -> Result<String, Error> {
+    let quagmire = quagmire(key, number)?;
+
+    latin_folded(text, |text| match direction {
+        Direction::Cipher => collect(quagmire.try_cipher(text.chars())),
+        Direction::Decipher => collect(quagmire.try_decipher(text.chars())),
+    })
+}
+
+/// Every cipher of the crate, set up for free text: the Vigenère family over
+/// printable Unicode as the app has always done, the textbook ciphers over
+/// `A..=Z` with the case of letters kept. Characters a cipher can't shift
+/// are passed through. Beaufort and Porta run over every Unicode scalar
+/// value instead, as they would move symbols into the surrogate gap of
+/// printable Unicode.
+#[must_use]
+pub fn registry() -> Vec<Box<dyn Cipher>> {
+    vec![
+        Box::new(Entry {
+            name: "Vigenère",
+            description: "Each key character shifts the text by its code point",
+            key_kind: KeyKind::Text,
+            run: Run::Vigenere(legacy),
+        }),
+        Box::new(Entry {
+            name: "Beaufort",
+            description: "Key minus text, deciphering is the same as ciphering",
+            key_kind: KeyKind::Text,
+            run: Run::Text {
+                parse: |key| scalar(key).map(|_| ()),
+                run: |key, text, _| {
+                    collect(
+                        Beaufort::new(scalar(key)?).try_cipher(text.chars()),
+                    )
+                },
+            },
+        }),
+        Box::new(Entry {
+            name: "Variant Beaufort",
+            description: "Text minus key, Vigenère run backwards",
+            key_kind: KeyKind::Text,
+            run: Run::Text {
+                parse: |key| legacy(key).map(|_| ()),
+                run: |key, text, direction| {
+                    let variant = VariantBeaufort::new(legacy(key)?);
+
+                    match direction {
+                        Direction::Cipher => {
+                            collect(variant.try_cipher(text.chars()))
+                        }
+                        Direction::Decipher => {
+                            collect(variant.try_decipher(text.chars()))
+                        }
+                    }
+                },
+            },
+        }),
+        Box::new(Entry {
+            name: "Autokey (plaintext)",
+            description: "The key is a primer followed by the plaintext",
+            key_kind: KeyKind::Text,
+            run: Run::Text {
+                parse: |key| legacy(key).map(|_| ()),
+                run: |key, text, direction| {
+                    let autokey =
+                        Autokey::new(legacy(key)?, AutokeyMode::Plaintext);
+
+                    match direction {
+                        Direction::Cipher => {
+                            collect(autokey.try_cipher(text.chars()))
+                        }
+                        Direction::Decipher => {
+                            collect(autokey.try_decipher(text.chars()))
+                        }
+                    }
+                },
+            },
+        }),
+        Box::new(Entry {
+            name: "Autokey (ciphertext)",
+            description: "The key is a primer followed by the ciphertext",
+            key_kind: KeyKind::Text,
+            run: Run::Text {
+                parse: |key| legacy(key).map(|_| ()),
+                run: |key, text, direction| {
+                    let autokey =
+                        Autokey::new(legacy(key)?, AutokeyMode::Ciphertext);
+
+                    match direction {
+                        Direction::Cipher => {
+                            collect(autokey.try_cipher(text.chars()))
+                        }
+                        Direction::Decipher => {
+                            collect(autokey.try_decipher(text.chars()))
+                        }
+                    }
+                },
+            },
+        }),
+        Box::new(Entry {
+            name: "Progressive key",
+            description: "Vigenère shifting one further on every pass of the key",
+            key_kind: KeyKind::Text,
+            run: Run::Vigenere(|key| {
+                legacy(key).map(|vigenere| {
+                    vigenere.with_progression(Progression::PerCycle(1))
+                })
+            }),
+        }),
+        Box::new(Entry {
+            name: "Trithemius",
+            description: "Every character shifted one further than the last, no key",
+            key_kind: KeyKind::None,
+            run: Run::Vigenere(|_| {
+                Vigenere::trithemius(UnicodeGraphic)
+                    .map(|vigenere| vigenere.with_foreign(Foreign::Keep))
+            }),
+        }),
+        Box::new(Entry {
+            name: "Gronsfeld",
+            description: "Vigenère with a key of digits, each a shift of 0 to 9",
+            key_kind: KeyKind::Digits,
+            run: Run::Vigenere(|key| {
+                Gronsfeld::new(key, UnicodeGraphic).map(|gronsfeld| {
+                    gronsfeld.vigenere().clone().with_foreign(Foreign::Keep)
+                })
+            }),
+        }),
+        Box::new(Entry {
+            name: "Running key",
+            description: "The key is a long text used once, such as a book",
+            key_kind: KeyKind::Book,
+            run: Run::Text {
+                parse: |key| {
+                    RunningKey::new(key, 0, UnicodeGraphic).map(|_| ())
+                },
+                run: |key, text, direction| {
+                    let running = RunningKey::new(key, 0, UnicodeGraphic)?
+                        .with_foreign(Foreign::Keep);
+
+                    match direction {
+                        Direction::Cipher => {
+                            collect(running.try_cipher(text.chars()))
+                        }
+                        Direction::Decipher => {
+                            collect(running.try_decipher(text.chars()))
+                        }
+                    }
+                },
+            },
+        }),
+        Box::new(Entry {
+            name: "Porta",
+            description: "Pairs of key characters swap the two alphabet halves",
+            key_kind: KeyKind::Text,
+            run: Run::Text {
+                parse: |key| scalar(key).map(|_| ()),
+                run: |key, text, _| {
+                    collect(Porta::new(scalar(key)?)?.try_cipher(text.chars()))
+                },
+            },
+        }),
+        Box::new(Entry {
+            name: "Quagmire I",
+            description: "Keyed plaintext alphabet; key is `KEYWORD INDICATOR`",
+            key_kind: KeyKind::Text,
+            run: Run::Text {
+                parse: |key| quagmire(key, 1).map(|_| ()),
+                run: |key, text, direction| {
+                    run_quagmire(key, text, direction, 1)
+                },
+            },
+        }),
+        Box::new(Entry {
+            name: "Quagmire II",
+            description: "Keyed ciphertext alphabet; key is `KEYWORD INDICATOR`",
+            key_kind: KeyKind::Text,
+            run: Run::Text {
+                parse: |key| quagmire(key, 2).map(|_| ()),
+                run: |key, text, direction| {
+                    run_quagmire(key, text, direction, 2)
+                },
+            },
+        }),
+        Box::new(Entry {
+            name: "Quagmire III",
+            description: "Both alphabets keyed alike; key is `KEYWORD INDICATOR`",
+            key_kind: KeyKind::Text,
+            run: Run::Text {
+                parse: |key| quagmire(key, 3).map(|_| ()),
+                run: |key, text, direction| {
+                    run_quagmire(key, text, direction, 3)
+                },
+            },
+        }),
+        Box::new(Entry {
+            name: "Quagmire IV",
+            description: "Alphabets keyed apart; key is `PLAIN CIPHER INDICATOR`",
+            key_kind: KeyKind::Text,
+            run: Run::Text {
+                parse: |key| quagmire(key, 4).map(|_| ()),
+                run: |key, text, direction| {
+                    run_quagmire(key, text, direction, 4)
+                },
+            },
+        }),
+        Box::new(Entry {
+            name: "Caesar",
+            description: "Every letter shifted alike; key is a number or a letter",
+            key_kind: KeyKind::Text,
+            run: Run::Text {
+                parse: |key| caesar_shift(key).map(|_| ()),
+                run: |key, text, direction| {
+                    let caesar =
+                        Caesar::new(caesar_shift(key)?, Symbols::latin())?
+                            .with_foreign(Foreign::Keep);
+
+                    latin_folded(text, |text| match direction {
+                        Direction::Cipher => {
+                            collect(caesar.try_cipher(text.chars()))
+                        }
+                        Direction::Decipher => {
+                            collect(caesar.try_decipher(text.chars()))
+                        }
+                    })
+                },
+            },
+        }),
+        Box::new(Entry {
+            name: "ROT13",
+            description: "Caesar with a shift of 13, its own inverse",
+            key_kind: KeyKind::None,
+            run: Run::Text {
+                parse: |_| Ok(()),
+                run: |_, text, _| {
+                    let rot13 = Caesar::rot13().with_foreign(Foreign::Keep);
+
+                    latin_folded(text, |text| {
+                        collect(rot13.try_cipher(text.chars()))
+                    })
+                },
+            },
+        }),
+        Box::new(Entry {
+            name: "Atbash",
+            description: "The alphabet read backwards, its own inverse",
+            key_kind: KeyKind::None,
+            run: Run::Text {
+                parse: |_| Ok(()),
+                run: |_, text, _| {
+                    let atbash = Atbash::new(Symbols::latin())?
+                        .with_foreign(Foreign::Keep);
+
+                    latin_folded(text, |text| {
+                        collect(atbash.try_cipher(text.chars()))
+                    })
+                },
+            },
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &str = "Attack at dawn!\nКириллическое сообщение 😀";
+
+    fn key_for(cipher: &dyn Cipher) -> String {
+        match (cipher.key_kind(), cipher.name()) {
+            (KeyKind::None, _) => String::new(),
+            (KeyKind::Digits, _) => "31415".into(),
+            (KeyKind::Book, _) => MESSAGE.repeat(2),
+            (_, "Quagmire IV") => "senory perctflight extra".into(),
+            (_, name) if name.starts_with("Quagmire") => "spring flower".into(),
+            (_, "Caesar") => "7".into(),
+            _ => "Lemon".into(),
+        }
+    }
+
+    #[test]
+    fn every_cipher_roundtrips() {
+        for cipher in registry() {
+            let name = cipher.name();
+            let key = key_for(cipher.as_ref());
+            let ok = |result: Result<String, Error>| {
+                result.unwrap_or_else(|err| panic!("{name}: {err}"))
+            };
+
+            assert!(cipher.parse_key(&key).is_ok(), "{name}");
+
+            let ciphered = ok(cipher.encrypt(&key, MESSAGE));
+            assert_ne!(ciphered, MESSAGE, "{name}");
+            assert_eq!(ok(cipher.decrypt(&key, &ciphered)), MESSAGE, "{name}");
+
+            let streamed = cipher
+                .encrypt_reader(&key, Box::new(MESSAGE.as_bytes()))
+                .and_then(|reader| Ok(io::read_to_string(reader)?));
+            assert_eq!(ok(streamed), ciphered, "{name}");
+
+            let streamed = cipher
+                .decrypt_reader(&key, Box::new(ciphered.as_bytes()))
+                .and_then(|reader| Ok(io::read_to_string(reader)?));
+            assert_eq!(ok(streamed), MESSAGE, "{name}");
+        }
+    }
+
+    fn assert_roundtrips(name: &str, message: &str) {
+        let registry = registry();
+        let cipher = registry
+            .iter()
+            .find(|cipher| cipher.name() == name)
+            .expect("Cipher is registered");
+
+        let ciphered = cipher
+            .encrypt("Key", message)
+            .unwrap_or_else(|err| panic!("{name}: {err}"));
+        assert_eq!(ciphered.chars().count(), message.chars().count(), "{name}");
+        assert_eq!(
+            cipher.decrypt("Key", &ciphered).ok().as_deref(),
+            Some(message),
+            "{name}"
+        );
+    }
+
+    #[test]
+    fn no_character_is_lost() {
+        assert_roundtrips("Beaufort", "Price: 5 € for № 7, ™ → ╔═╗");
+        assert_roundtrips("Porta", "山川と家、小さな字 €5");
+    }
+
+    #[test]
+    fn textbook_ciphers() {
+        let registry = registry();
+        let find = |name| {
+            registry
+                .iter()
+                .find(|cipher| cipher.name() == name)
+                .expect("Cipher is registered")
+        };
+
+        let caesar = find("Caesar");
+        assert_eq!(
+            caesar.encrypt("3", "Veni, vidi").ok().as_deref(),
+            Some("Yhql, ylgl")
+        );
+        assert_eq!(
+            caesar.encrypt("D", "Veni, vidi").ok().as_deref(),
+            Some("Yhql, ylgl")
+        );
+        assert!(matches!(
+            caesar.parse_key("3x"),
+            Err(KeyError::OutsideAlphabet {
+                position: 1,
+                ch: 'x'
+            })
+        ));
+
+        let rot13 = find("ROT13");
+        assert_eq!(rot13.encrypt("", "Hello").ok().as_deref(), Some("Uryyb"));
+
+        let atbash = find("Atbash");
+        assert_eq!(
+            atbash.encrypt("", "Wizard").ok().as_deref(),
+            Some("Draziw")
+        );
+
+        let vigenere = find("Vigenère");
+        assert!(matches!(
+            vigenere.parse_key("Ключ"),
+            Err(KeyError::Policy(_))
+        ));
+
+        let legacy = legacy("Key").expect("Key is valid");
+        assert_eq!(
+            vigenere.encrypt("Key", MESSAGE).ok(),
+            Some(legacy.cipher(MESSAGE.chars()).collect())
+        );
+    }
+}
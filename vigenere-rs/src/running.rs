@@ -0,0 +1,163 @@
+use std::{io, path::Path};
+
+use crate::{
+    Alphabet, CipherError, Foreign, IterStream, KeyError, KeyMapping,
+    UnicodeGraphic, Vigenere, unwrap_chars,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BookError {
+    #[error("Couldn't read the key text: {0}")]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Key(#[from] KeyError),
+}
+
+/// Running-key Vigenère: the key is a long text, usually a book, used once
+/// from start to end instead of cycling a short password.
+///
+/// Running out of key text is an error, [`CipherError::KeyExhausted`].
+#[derive(Debug, Clone)]
+pub struct RunningKey<A: Alphabet = UnicodeGraphic> {
+    vigenere: Vigenere<A>,
+}
+
+impl<A: Alphabet> RunningKey<A> {
+    /// Takes the key from `book`, starting at its `offset`-th character.
+    /// Characters of the book that are not in the alphabet are skipped.
+    pub fn new(
+        book: &str,
+        offset: usize,
+        alphabet: A,
+    ) -> Result<Self, KeyError> {
+        let key = book
+            .chars()
+            .skip(offset)
+            .filter(|&ch| alphabet.contains(ch))
+            .collect();
+
+        Vigenere::with_alphabet(key, alphabet).map(|vigenere| Self { vigenere })
+    }
+
+    /// Reads the book from the UTF-8 file at `path`, see [`RunningKey::new`].
+    pub fn from_file(
+        path: impl AsRef<Path>,
+        offset: usize,
+        alphabet: A,
+    ) -> Result<Self, BookError> {
+        let book = std::fs::read_to_string(path)?;
+
+        Ok(Self::new(&book, offset, alphabet)?)
+    }
+
+    #[must_use]
+    pub fn with_key_mapping(self, mapping: KeyMapping) -> Self {
+        Self {
+            vigenere: self.vigenere.with_key_mapping(mapping),
+        }
+    }
+
+    #[must_use]
+    pub fn with_foreign(self, foreign: Foreign) -> Self {
+        Self {
+            vigenere: self.vigenere.with_foreign(foreign),
+        }
+    }
+
+    pub const fn vigenere(&self) -> &Vigenere<A> {
+        &self.vigenere
+    }
+
+    /// Number of key characters, the longest message that can be ciphered.
+    pub fn key_len(&self) -> usize {
+        self.vigenere.shifts.len()
+    }
+
+    pub fn cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = char> + use<'_, A, I> {
+        unwrap_chars(self.try_cipher(inner))
+    }
+
+    pub fn decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = char> + use<'_, A, I> {
+        unwrap_chars(self.try_decipher(inner))
+    }
+
+    pub fn try_cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I> {
+        self.vigenere.try_cipher_with(inner, self.key_stream())
+    }
+
+    pub fn try_decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I> {
+        self.vigenere.try_decipher_with(inner, self.key_stream())
+    }
+
+    /// Key text as a [`crate::KeyStream`] that runs out at its end.
+    pub fn key_stream(
+        &self,
+    ) -> IterStream<impl Iterator<Item = usize> + Clone + use<'_, A>> {
+        IterStream::new(self.vigenere.shifts.iter().copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Symbols;
+
+    const BOOK: &str =
+        "IF THE BOOK IS A NOVEL, ERRORS CAN OCCUR IN SEVERAL PLACES.";
+
+    fn running() -> RunningKey<Symbols> {
+        RunningKey::new(BOOK, 20, Symbols::latin())
+            .expect("Book has latin letters")
+            .with_key_mapping(KeyMapping::Index)
+    }
+
+    #[test]
+    fn book_key_from_offset() {
+        let running = running();
+        assert_eq!(running.key_len(), 31);
+
+        let ciphered = running
+            .try_cipher("DEFENDTHEEASTWALLOFTHECASTLE".chars())
+            .collect::<Result<String, _>>();
+        assert_eq!(ciphered.as_deref(), Ok("HPJVERKZGENGVYUCTBXXCITADIWE"));
+
+        let result = running.decipher("HPJVERKZGENGVYUCTBXXCITADIWE".chars());
+        assert_eq!(result.collect::<String>(), "DEFENDTHEEASTWALLOFTHECASTLE");
+    }
+
+    #[test]
+    fn key_shorter_than_message() {
+        let running = running().with_foreign(Foreign::Keep);
+        let message = "ATTACK AT DAWN ".repeat(3);
+
+        let ciphered = running
+            .try_cipher(message.chars())
+            .collect::<Result<String, _>>();
+
+        assert_eq!(ciphered, Err(CipherError::KeyExhausted { position: 38 }));
+    }
+
+    #[test]
+    fn missing_book() {
+        let result =
+            RunningKey::from_file("no/such/book.txt", 0, Symbols::latin());
+
+        assert!(matches!(result, Err(BookError::Io(_))));
+        assert!(matches!(
+            RunningKey::new(BOOK, 1000, Symbols::latin()),
+            Err(KeyError::Empty)
+        ));
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{
+    Alphabet, Beaufort, CipherError, Foreign, KeyError, Symbols,
+    UnicodeGraphic, Vigenere,
+};
+
+/// Caesar cipher: every symbol moves the same number of places along the
+/// alphabet.
+#[derive(Debug, Clone)]
+pub struct Caesar<A: Alphabet = UnicodeGraphic> {
+    vigenere: Vigenere<A>,
+}
+
+/// Atbash cipher: the alphabet is read backwards, so the first symbol
+/// swaps with the last. It is its own inverse.
+#[derive(Debug, Clone)]
+pub struct Atbash<A: Alphabet = UnicodeGraphic> {
+    beaufort: Beaufort<A>,
+}
+
+impl Caesar<Symbols> {
+    /// Caesar with a shift of 13 over `A..=Z`, its own inverse.
+    #[must_use]
+    pub fn rot13() -> Self {
+        Self::new(13, Symbols::latin()).expect("Latin alphabet is not empty")
+    }
+}
+
+impl<A: Alphabet> Caesar<A> {
+    /// Fails with [`KeyError::Empty`] only for an alphabet with no symbols.
+    pub fn new(shift: usize, alphabet: A) -> Result<Self, KeyError> {
+        let key = alphabet.char_at(shift % alphabet.len().max(1));
+        let key = key.ok_or(KeyError::Empty)?;
+
+        Ok(Self {
+            vigenere: Vigenere::from_shifts(key.into(), vec![shift], alphabet),
+        })
+    }
+
+    #[must_use]
+    pub fn with_foreign(self, foreign: Foreign) -> Self {
+        Self {
+            vigenere: self.vigenere.with_foreign(foreign),
+        }
+    }
+
+    /// The wrapped single-shift Vigenère, for everything not covered here.
+    pub const fn vigenere(&self) -> &Vigenere<A> {
+        &self.vigenere
+    }
+
+    pub fn cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = char> + use<'_, A, I> {
+        self.vigenere.cipher(inner)
+    }
+
+    pub fn decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = char> + use<'_, A, I> {
+        self.vigenere.decipher(inner)
+    }
+
+    pub fn try_cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I> {
+        self.vigenere.try_cipher(inner)
+    }
+
+    pub fn try_decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I> {
+        self.vigenere.try_decipher(inner)
+    }
+}
+
+impl<A: Alphabet> Atbash<A> {
+    /// Fails with [`KeyError::Empty`] only for an alphabet with no symbols.
+    pub fn new(alphabet: A) -> Result<Self, KeyError> {
+        // Beaufort with the last symbol as the key: `C = (len - 1) - P`.
+        let last = alphabet.len().checked_sub(1).ok_or(KeyError::Empty)?;
+        let key = alphabet.char_at(last).ok_or(KeyError::Empty)?;
+        let vigenere = Vigenere::from_shifts(key.into(), vec![last], alphabet);
+
+        Ok(Self {
+            beaufort: Beaufort::new(vigenere),
+        })
+    }
+
+    #[must_use]
+    pub fn with_foreign(self, foreign: Foreign) -> Self {
+        let vigenere = self.beaufort.vigenere.with_foreign(foreign);
+
+        Self {
+            beaufort: Beaufort::new(vigenere),
+        }
+    }
+
+    pub fn cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = char> + use<'_, A, I> {
+        self.beaufort.cipher(inner)
+    }
+
+    pub fn decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = char> + use<'_, A, I> {
+        self.beaufort.decipher(inner)
+    }
+
+    pub fn try_cipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I> {
+        self.beaufort.try_cipher(inner)
+    }
+
+    pub fn try_decipher<I: Iterator<Item = char>>(
+        &self,
+        inner: I,
+    ) -> impl Iterator<Item = Result<char, CipherError>> + use<'_, A, I> {
+        self.beaufort.try_decipher(inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caesar_and_rot13() {
+        let caesar = Caesar::new(3, Symbols::latin()).expect("Not empty");
+        let ciphered = caesar.cipher("VENIVIDIVICI".chars());
+        assert_eq!(ciphered.collect::<String>(), "YHQLYLGLYLFL");
+
+        let rot13 = Caesar::rot13();
+        let ciphered = rot13.cipher("HELLOWORLD".chars()).collect::<String>();
+        assert_eq!(ciphered, "URYYBJBEYQ");
+        assert_eq!(
+            rot13.cipher(ciphered.chars()).collect::<String>(),
+            "HELLOWORLD"
+        );
+    }
+
+    #[test]
+    fn atbash() {
+        let atbash = Atbash::new(Symbols::latin()).expect("Not empty");
+        let ciphered = atbash.cipher("WIZARD".chars()).collect::<String>();
+        assert_eq!(ciphered, "DRAZIW");
+        assert_eq!(
+            atbash.decipher(ciphered.chars()).collect::<String>(),
+            "WIZARD"
+        );
+
+        let atbash = Atbash::new(Symbols::russian()).expect("Not empty");
+        let ciphered = atbash.cipher("АБВ ЭЮЯ".chars()).collect::<String>();
+        assert_eq!(ciphered, "ЯЮЭВБА");
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{Alphabet, CipherError, RepeatingStream, UnicodeGraphic, Vigenere};
+
+/// Vigenère that remembers where it stopped, so text can be fed in chunks
+/// and ciphering can start anywhere in the key.
+///
+/// `position` counts input characters, `key_offset` is the index of the key
+/// character the next alphabet character is shifted by.
+#[derive(Debug, Clone)]
+pub struct VigenereState<A: Alphabet = UnicodeGraphic> {
+    vigenere: Vigenere<A>,
+    key_pos: usize,
+    position: usize,
+}
+
+impl<A: Alphabet> VigenereState<A> {
+    pub const fn new(vigenere: Vigenere<A>) -> Self {
+        Self {
+            vigenere,
+            key_pos: 0,
+            position: 0,
+        }
+    }
+
+    /// Starts at `key_offset` in the key instead of its first character.
+    pub fn with_key_offset(vigenere: Vigenere<A>, key_offset: usize) -> Self {
+        let mut state = Self::new(vigenere);
+        state.seek_key(key_offset);
+        state
+    }
+
+    pub const fn vigenere(&self) -> &Vigenere<A> {
+        &self.vigenere
+    }
+
+    pub fn into_inner(self) -> Vigenere<A> {
+        self.vigenere
+    }
+
+    pub const fn key_offset(&self) -> usize {
+        self.key_pos
+    }
+
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Moves to the character at `position` of the text.
+    ///
+    /// The key offset is worked out as if every character before `position`
+    /// used up a key character, which holds unless some of them were passed
+    /// through with [`crate::Foreign::Keep`]. Use
+    /// [`VigenereState::seek_key`] for such texts.
+    pub fn seek(&mut self, position: usize) {
+        self.position = position;
+        self.key_pos = position % self.vigenere.period();
+    }
+
+    /// Moves to `key_offset` in the key, leaving the position alone.
+    pub fn seek_key(&mut self, key_offset: usize) {
+        self.key_pos = key_offset % self.vigenere.period();
+    }
+
+    pub fn cipher(&mut self, chunk: &str) -> Result<String, CipherError> {
+        let mut output = String::with_capacity(chunk.len());
+        self.cipher_into(chunk, &mut output)?;
+
+        Ok(output)
+    }
+
+    pub fn decipher(&mut self, chunk: &str) -> Result<String, CipherError> {
+        let mut output = String::with_capacity(chunk.len());
+        self.decipher_into(chunk, &mut output)?;
+
+        Ok(output)
+    }
+
+    /// Appends the ciphered `chunk` to `output`. On error, `output` holds
+    /// everything before the offending character.
+    pub fn cipher_into(
+        &mut self,
+        chunk: &str,
+        output: &mut String,
+    ) -> Result<(), CipherError> {
+        let alphabet = &self.vigenere.alphabet;
+        let shift = |ch, shift| alphabet.lshift(ch, shift);
+
+        Self::feed(
+            &self.vigenere,
+            &mut self.key_pos,
+            &mut self.position,
+            chunk,
+            shift,
+            output,
+        )
+    }
+
+    /// Appends the deciphered `chunk` to `output`. On error, `output` holds
+    /// everything before the offending character.
+    pub fn decipher_into(
+        &mut self,
+        chunk: &str,
+        output: &mut String,
+    ) -> Result<(), CipherError> {
+        let alphabet = &self.vigenere.alphabet;
+        let shift = |ch, shift| alphabet.rshift(ch, shift);
+
+        Self::feed(
+            &self.vigenere,
+            &mut self.key_pos,
+            &mut self.position,
+            chunk,
+            shift,
+            output,
+        )
+    }
+
+    fn feed(
+        vigenere: &Vigenere<A>,
+        key_pos: &mut usize,
+        position: &mut usize,
+        chunk: &str,
+        mut shift: impl FnMut(char, usize) -> Option<char>,
+        output: &mut String,
+    ) -> Result<(), CipherError> {
+        let mut key = RepeatingStream::new(vigenere, *key_pos);
+
+        for ch in chunk.chars() {
+            let step = vigenere.step(ch, *position, &mut key, &mut shift);
+            *position += 1;
+            *key_pos = key.key_pos;
+
+            if let Some(ch) = step.transpose()? {
+                output.push(ch);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Symbols;
+
+    fn lemon() -> Vigenere<Symbols> {
+        Vigenere::classical("LEMON".into(), Symbols::latin())
+            .expect("Vigenere couldn't be created")
+    }
+
+    #[test]
+    fn chunks_carry_key_position() {
+        let mut state = VigenereState::new(lemon());
+
+        let mut result = String::new();
+        for chunk in ["ATT", "ACKA", "", "TDAWN"] {
+            result += &state.cipher(chunk).expect("Chunk is latin");
+        }
+
+        assert_eq!(result, "LXFOPVEFRNHR");
+        assert_eq!(state.position(), 12);
+        assert_eq!(state.key_offset(), 2);
+    }
+
+    #[test]
+    fn seek_into_the_middle() {
+        let mut state = VigenereState::new(lemon());
+
+        state.seek(7);
+        assert_eq!(state.decipher("FRNHR").as_deref(), Ok("TDAWN"));
+
+        let mut state = VigenereState::with_key_offset(lemon(), 12);
+        assert_eq!(state.cipher("TDAWN").as_deref(), Ok("FRNHR"));
+    }
+
+    #[test]
+    fn append_to_ciphertext() {
+        let mut state = VigenereState::new(lemon());
+        let mut ciphertext = state.cipher("ATTACK").expect("Chunk is latin");
+
+        let mut resumed = VigenereState::new(lemon());
+        resumed.seek(ciphertext.chars().count());
+        ciphertext += &resumed.cipher("ATDAWN").expect("Chunk is latin");
+
+        assert_eq!(ciphertext, "LXFOPVEFRNHR");
+    }
+}
@@ -0,0 +1,183 @@
+//! Published Vigenère test vectors, checked against the classical mode.
+
+use crate::{
+    Autokey, AutokeyMode, Beaufort, Gronsfeld, Porta, Symbols, VariantBeaufort,
+    Vigenere,
+};
+
+struct Vector {
+    key: &'static str,
+    plaintext: &'static str,
+    ciphertext: &'static str,
+}
+
+const LATIN: &[Vector] = &[
+    Vector {
+        key: "LEMON",
+        plaintext: "ATTACKATDAWN",
+        ciphertext: "LXFOPVEFRNHR",
+    },
+    Vector {
+        key: "DECEPTIVE",
+        plaintext: "WEAREDISCOVEREDSAVEYOURSELF",
+        ciphertext: "ZICVTWQNGRZGVTWAVZHCQYGLMGJ",
+    },
+    Vector {
+        key: "OCULORHINOLARINGOLOGY",
+        plaintext: "ATTACKINGTONIGHT",
+        ciphertext: "OVNLQBPVTHZNZOUZ",
+    },
+    Vector {
+        key: "ABCD",
+        plaintext: "CRYPTOISSHORTFORCRYPTOGRAPHY",
+        ciphertext: "CSASTPKVSIQUTGQUCSASTPIUAQJB",
+    },
+];
+
+#[test]
+fn latin_vectors_cipher() {
+    for vector in LATIN {
+        let vigenere = Vigenere::classical(vector.key.into(), Symbols::latin())
+            .expect("Vigenere couldn't be created");
+
+        let result = vigenere.cipher(vector.plaintext.chars());
+
+        assert_eq!(result.collect::<String>(), vector.ciphertext);
+    }
+}
+
+#[test]
+fn latin_vectors_decipher() {
+    for vector in LATIN {
+        let vigenere = Vigenere::classical(vector.key.into(), Symbols::latin())
+            .expect("Vigenere couldn't be created");
+
+        let result = vigenere.decipher(vector.ciphertext.chars());
+
+        assert_eq!(result.collect::<String>(), vector.plaintext);
+    }
+}
+
+#[test]
+fn trithemius_vector() {
+    let trithemius =
+        Vigenere::trithemius(Symbols::latin()).expect("Alphabet is not empty");
+
+    let result = trithemius.cipher("ATTACKATDAWN".chars());
+    assert_eq!(result.collect::<String>(), "AUVDGPGALJGY");
+}
+
+const AUTOKEY: &[Vector] = &[
+    Vector {
+        key: "QUEENLY",
+        plaintext: "ATTACKATDAWN",
+        ciphertext: "QNXEPVYTWTWP",
+    },
+    Vector {
+        key: "DECEPTIVE",
+        plaintext: "WEAREDISCOVEREDSAVEYOURSELF",
+        ciphertext: "ZICVTWQNGKZEIIGASXSTSLVVWLA",
+    },
+];
+
+#[test]
+fn autokey_vectors() {
+    for vector in AUTOKEY {
+        let vigenere = Vigenere::classical(vector.key.into(), Symbols::latin())
+            .expect("Vigenere couldn't be created");
+        let autokey = Autokey::new(vigenere, AutokeyMode::Plaintext);
+
+        let result = autokey.cipher(vector.plaintext.chars());
+        assert_eq!(result.collect::<String>(), vector.ciphertext);
+
+        let result = autokey.decipher(vector.ciphertext.chars());
+        assert_eq!(result.collect::<String>(), vector.plaintext);
+    }
+}
+
+const BEAUFORT: &[Vector] = &[Vector {
+    key: "FORTIFICATION",
+    plaintext: "DEFENDTHEEASTWALLOFTHECASTLE",
+    ciphertext: "CKMPVCPVWPIWUJOGIUAPVWRIWUUK",
+}];
+
+const VARIANT_BEAUFORT: &[Vector] = &[Vector {
+    key: "FORTIFICATION",
+    plaintext: "DEFENDTHEEASTWALLOFTHECASTLE",
+    ciphertext: "YQOLFYLFELSEGRMUSGALFEJSEGGQ",
+}];
+
+#[test]
+fn beaufort_vectors() {
+    for vector in BEAUFORT {
+        let vigenere = Vigenere::classical(vector.key.into(), Symbols::latin())
+            .expect("Vigenere couldn't be created");
+        let beaufort = Beaufort::new(vigenere);
+
+        let result = beaufort.cipher(vector.plaintext.chars());
+        assert_eq!(result.collect::<String>(), vector.ciphertext);
+
+        let result = beaufort.decipher(vector.ciphertext.chars());
+        assert_eq!(result.collect::<String>(), vector.plaintext);
+    }
+
+    for vector in VARIANT_BEAUFORT {
+        let vigenere = Vigenere::classical(vector.key.into(), Symbols::latin())
+            .expect("Vigenere couldn't be created");
+        let variant = VariantBeaufort::new(vigenere);
+
+        let result = variant.cipher(vector.plaintext.chars());
+        assert_eq!(result.collect::<String>(), vector.ciphertext);
+
+        let result = variant.decipher(vector.ciphertext.chars());
+        assert_eq!(result.collect::<String>(), vector.plaintext);
+    }
+}
+
+const GRONSFELD: &[Vector] = &[Vector {
+    key: "31415",
+    plaintext: "ATTACKATDAWN",
+    ciphertext: "DUXBHNBXEFZO",
+}];
+
+#[test]
+fn gronsfeld_vectors() {
+    for vector in GRONSFELD {
+        let gronsfeld = Gronsfeld::new(vector.key, Symbols::latin())
+            .expect("Gronsfeld couldn't be created");
+
+        let result = gronsfeld.cipher(vector.plaintext.chars());
+        assert_eq!(result.collect::<String>(), vector.ciphertext);
+
+        let result = gronsfeld.decipher(vector.ciphertext.chars());
+        assert_eq!(result.collect::<String>(), vector.plaintext);
+    }
+}
+
+const PORTA: &[Vector] = &[
+    Vector {
+        key: "FORTIFICATION",
+        plaintext: "DEFENDTHEEASTWALLOFTHECASTLE",
+        ciphertext: "SYNNJSCVRNRLAHUTUKUCVRYRLANY",
+    },
+    Vector {
+        key: "PORTA",
+        plaintext: "ATTACKATDAWN",
+        ciphertext: "UMLWPRULZNCG",
+    },
+];
+
+#[test]
+fn porta_vectors() {
+    for vector in PORTA {
+        let vigenere = Vigenere::classical(vector.key.into(), Symbols::latin())
+            .expect("Vigenere couldn't be created");
+        let porta = Porta::new(vigenere).expect("Latin alphabet is even");
+
+        let result = porta.cipher(vector.plaintext.chars());
+        assert_eq!(result.collect::<String>(), vector.ciphertext);
+
+        let result = porta.decipher(vector.ciphertext.chars());
+        assert_eq!(result.collect::<String>(), vector.plaintext);
+    }
+}